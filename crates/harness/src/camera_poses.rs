@@ -0,0 +1,126 @@
+//! Camera pose export for re-rendering captures in external tools.
+
+use std::path::Path;
+
+use bevy::camera::ScalingMode;
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::presets::CameraPreset;
+
+/// File name of the camera pose file written into each session directory
+pub const CAMERA_POSES_FILE: &str = "cameras.json";
+
+/// Coordinate convention stored alongside the poses
+const CONVENTION: &str = "Right-handed, Y up. The camera looks down its local -Z axis with +Y up. \
+	world_from_camera is a column-major 4x4 matrix (four columns of four floats) mapping \
+	camera space to world space. Angles are in degrees, distances in world units.";
+
+/// Position and projection of a single preset's camera
+#[derive(Serialize, Debug, Clone)]
+pub struct CameraPose {
+	pub preset: String,
+	/// Column-major camera-to-world matrix
+	pub world_from_camera: [[f32; 4]; 4],
+	/// Vertical field of view, for perspective cameras
+	pub fov_y_degrees: Option<f32>,
+	/// View width in world units, for orthographic cameras with a fixed width
+	pub ortho_width: Option<f32>,
+	/// View height in world units, for orthographic cameras with a fixed height
+	pub ortho_height: Option<f32>,
+	pub near: f32,
+	pub far: f32,
+}
+
+impl CameraPose {
+	/// Pose of the camera the harness places for `preset`, with the preset's own projection
+	pub fn from_preset(preset: &CameraPreset) -> Self {
+		Self::with_projection(preset, &preset.projection())
+	}
+
+	/// Pose of `preset`'s camera rendered through `projection`
+	pub fn with_projection(preset: &CameraPreset, projection: &Projection) -> Self {
+		let (fov_y_degrees, ortho_width, ortho_height, near, far) = match projection {
+			Projection::Perspective(perspective) => (
+				Some(perspective.fov.to_degrees()),
+				None,
+				None,
+				perspective.near,
+				perspective.far,
+			),
+			Projection::Orthographic(orthographic) => {
+				let (width, height) = match orthographic.scaling_mode {
+					ScalingMode::Fixed { width, height } => (Some(width), Some(height)),
+					ScalingMode::FixedVertical { viewport_height } => (None, Some(viewport_height)),
+					ScalingMode::FixedHorizontal { viewport_width } => (Some(viewport_width), None),
+					_ => (None, None),
+				};
+				(None, width, height, orthographic.near, orthographic.far)
+			}
+			Projection::Custom(_) => (None, None, None, 0.0, 0.0),
+		};
+
+		Self {
+			preset: preset.name.to_string(),
+			world_from_camera: preset.transform().to_matrix().to_cols_array_2d(),
+			fov_y_degrees,
+			ortho_width,
+			ortho_height,
+			near,
+			far,
+		}
+	}
+}
+
+/// Contents of the per-session camera pose file
+#[derive(Serialize, Debug, Clone)]
+pub struct CameraPoseFile {
+	/// Description of the coordinate and matrix conventions
+	pub convention: &'static str,
+	pub width: u32,
+	pub height: u32,
+	pub aspect_ratio: f32,
+	pub cameras: Vec<CameraPose>,
+}
+
+impl CameraPoseFile {
+	pub fn new(cameras: impl IntoIterator<Item = CameraPose>, width: u32, height: u32) -> Self {
+		Self {
+			convention: CONVENTION,
+			width,
+			height,
+			aspect_ratio: width as f32 / height.max(1) as f32,
+			cameras: cameras.into_iter().collect(),
+		}
+	}
+
+	/// Writes the poses as pretty-printed JSON
+	pub fn write(&self, path: &Path) -> std::io::Result<()> {
+		let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+		std::fs::write(path, json)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn top_down_pose_exports_fixed_extent() {
+		let area = Rect::new(-20.0, -10.0, 20.0, 10.0);
+		let pose = CameraPose::from_preset(&CameraPreset::top_down("map", area, 50.0));
+
+		assert_eq!(pose.fov_y_degrees, None);
+		assert_eq!(pose.ortho_width, Some(40.0));
+		assert_eq!(pose.ortho_height, Some(20.0));
+	}
+
+	#[test]
+	fn perspective_pose_has_no_ortho_extent() {
+		let pose = CameraPose::from_preset(&CameraPreset::polar("front", 10.0, 5.0, 0.0));
+
+		assert!(pose.fov_y_degrees.is_some());
+		assert_eq!(pose.ortho_width, None);
+		assert_eq!(pose.ortho_height, None);
+	}
+}
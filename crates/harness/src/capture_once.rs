@@ -0,0 +1,64 @@
+//! One-shot headless capture for quick scripts.
+
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::config::ScreenshotConfig;
+use crate::headless::{headless_plugins, headless_runner};
+use crate::plugin::ScreenshotHarnessPlugin;
+use crate::presets::CameraPreset;
+
+/// Renders the scene added by `build` from `camera` and saves a single PNG to `out_path`.
+///
+/// Runs a headless app with the harness's default pre-roll and lighting, then returns
+/// once the frame is written. The session is captured into a uniquely named scratch
+/// directory under the system temp dir, which is removed afterwards. The camera's roll is ignored: it
+/// looks along its forward vector with +Y up.
+///
+/// Headless rendering still reads the harness CLI flags (`--backend`, `--adapter`, ...),
+/// so unrelated command-line arguments are rejected.
+pub fn capture_once(
+	build: impl FnOnce(&mut App),
+	camera: Transform,
+	width: u32,
+	height: u32,
+	out_path: impl AsRef<Path>,
+) -> io::Result<()> {
+	let scratch_dir = tempfile::Builder::new()
+		.prefix("bevy-screenshot-harness-")
+		.tempdir()?;
+	let eye = camera.translation;
+	let preset = CameraPreset::explicit("capture", eye, eye + *camera.forward());
+
+	let config = ScreenshotConfig::default()
+		.with_presets(vec![preset])
+		.with_resolution(width, height)
+		.with_output_dir(scratch_dir.path());
+	let (sender, receiver) = crossbeam_channel::bounded(1);
+
+	let mut app = App::new();
+	app.add_plugins(headless_plugins(None))
+		.add_plugins(headless_runner())
+		.add_plugins(ScreenshotHarnessPlugin::with_config(config).with_result_sender(sender));
+	build(&mut app);
+
+	let exit = app.run();
+	let captured = receiver
+		.try_recv()
+		.ok()
+		.filter(|_| exit.is_success())
+		.and_then(|results| results.captured_paths.into_iter().next());
+
+	match captured {
+		Some(path) => {
+			let out_path = out_path.as_ref();
+			if let Some(parent) = out_path.parent() {
+				std::fs::create_dir_all(parent)?;
+			}
+			std::fs::copy(path, out_path).map(|_| ())
+		}
+		None => Err(io::Error::other("capture did not complete")),
+	}
+}
@@ -1,17 +1,112 @@
 //! Session cleanup utilities.
 
-use std::path::Path;
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::state::unix_millis;
+
+/// Which screenshot sessions to keep when a new session starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetainPolicy {
+	/// Keep the most recent N sessions; 0 keeps everything
+	Count(usize),
+	/// Keep sessions younger than the given age
+	Age(Duration),
+	/// Keep the newest sessions whose combined size fits in this many bytes
+	Budget(u64),
+}
+
+impl Default for RetainPolicy {
+	fn default() -> Self {
+		Self::Count(5)
+	}
+}
+
+/// Removes sessions in `screenshot_dir` that fall outside `policy`, returning their paths.
+pub fn cleanup_sessions(screenshot_dir: &Path, policy: RetainPolicy) -> Vec<PathBuf> {
+	let planned = plan_cleanup(screenshot_dir, policy);
+	for path in &planned {
+		let _ = std::fs::remove_dir_all(path);
+	}
+	planned
+}
+
+/// Sessions in `screenshot_dir` that [`cleanup_sessions`] would remove, without touching
+/// the filesystem.
+pub fn plan_cleanup(screenshot_dir: &Path, policy: RetainPolicy) -> Vec<PathBuf> {
+	let sessions = sessions(screenshot_dir);
+
+	match policy {
+		RetainPolicy::Count(0) => Vec::new(),
+		RetainPolicy::Count(count) => sessions
+			.into_iter()
+			.skip(count)
+			.map(|(_, path)| path)
+			.collect(),
+		RetainPolicy::Age(max_age) => {
+			let cutoff = unix_millis().saturating_sub(max_age.as_millis() as u64);
+			sessions
+				.into_iter()
+				.filter(|(timestamp, _)| *timestamp < cutoff)
+				.map(|(_, path)| path)
+				.collect()
+		}
+		RetainPolicy::Budget(max_bytes) => {
+			let mut total = 0u64;
+			sessions
+				.into_iter()
+				.filter(|(_, path)| {
+					total = total.saturating_add(dir_size(path));
+					total > max_bytes
+				})
+				.map(|(_, path)| path)
+				.collect()
+		}
+	}
+}
 
 /// Removes old screenshot sessions, keeping only the most recent `retain_count`.
 ///
-/// Session directories are identified by their numeric (timestamp) names.
+/// Session directories are identified by their numeric (timestamp) name prefix.
 pub fn cleanup_old_sessions(screenshot_dir: &Path, retain_count: usize) {
-	if retain_count == 0 {
-		return;
-	}
+	cleanup_sessions(screenshot_dir, RetainPolicy::Count(retain_count));
+}
+
+/// Removes screenshot sessions whose timestamp is more than `max_age` in the past.
+pub fn cleanup_sessions_older_than(screenshot_dir: &Path, max_age: Duration) {
+	cleanup_sessions(screenshot_dir, RetainPolicy::Age(max_age));
+}
+
+/// Removes the oldest screenshot sessions once the newest ones add up to more than `max_bytes`.
+///
+/// Files that can't be read count as zero bytes rather than aborting the cleanup.
+pub fn cleanup_sessions_to_budget(screenshot_dir: &Path, max_bytes: u64) {
+	cleanup_sessions(screenshot_dir, RetainPolicy::Budget(max_bytes));
+}
+
+/// Total size of the files under `path`, skipping entries that can't be read.
+fn dir_size(path: &Path) -> u64 {
+	let Ok(entries) = std::fs::read_dir(path) else {
+		return 0;
+	};
+
+	entries
+		.filter_map(|e| e.ok())
+		.map(|e| match e.file_type() {
+			Ok(file_type) if file_type.is_dir() => dir_size(&e.path()),
+			Ok(_) => e.metadata().map_or(0, |metadata| metadata.len()),
+			Err(_) => 0,
+		})
+		.sum()
+}
 
+/// Session directories with their millisecond timestamps, newest first.
+///
+/// Matches `{timestamp}` and labeled `{timestamp}_{label}` names.
+fn sessions(screenshot_dir: &Path) -> Vec<(u64, PathBuf)> {
 	let Ok(entries) = std::fs::read_dir(screenshot_dir) else {
-		return;
+		return Vec::new();
 	};
 
 	let mut sessions: Vec<_> = entries
@@ -19,15 +114,138 @@ pub fn cleanup_old_sessions(screenshot_dir: &Path, retain_count: usize) {
 		.filter(|e| e.path().is_dir())
 		.filter_map(|e| {
 			let name = e.file_name().to_string_lossy().into_owned();
-			name.chars()
-				.all(|c| c.is_ascii_digit())
-				.then(|| (name, e.path()))
+			let prefix = name
+				.split_once('_')
+				.map_or(name.as_str(), |(prefix, _)| prefix);
+			if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_digit()) {
+				return None;
+			}
+			let timestamp: u64 = prefix.parse().ok()?;
+			Some((timestamp, e.path()))
 		})
 		.collect();
 
-	sessions.sort_by(|a, b| b.0.cmp(&a.0));
+	sessions.sort_by_key(|(timestamp, _)| Reverse(*timestamp));
+	sessions
+}
 
-	for (_, path) in sessions.into_iter().skip(retain_count) {
-		let _ = std::fs::remove_dir_all(path);
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Creates a session directory holding a single file of `bytes` bytes
+	fn make_session(dir: &Path, name: &str, bytes: usize) -> PathBuf {
+		let path = dir.join(name);
+		std::fs::create_dir_all(&path).unwrap();
+		std::fs::write(path.join("wide.png"), vec![0u8; bytes]).unwrap();
+		path
+	}
+
+	#[test]
+	fn age_policy_removes_sessions_past_the_cutoff() {
+		let dir = tempfile::tempdir().unwrap();
+		let now = unix_millis();
+		let old = make_session(dir.path(), &(now - 10 * 60 * 1000).to_string(), 1);
+		let older = make_session(dir.path(), &(now - 60 * 60 * 1000).to_string(), 1);
+		let recent = make_session(dir.path(), &(now - 1000).to_string(), 1);
+
+		let max_age = Duration::from_secs(5 * 60);
+		let mut planned = plan_cleanup(dir.path(), RetainPolicy::Age(max_age));
+		planned.sort();
+		let mut expected = vec![old.clone(), older.clone()];
+		expected.sort();
+		assert_eq!(planned, expected);
+
+		cleanup_sessions_older_than(dir.path(), max_age);
+		assert!(!old.exists());
+		assert!(!older.exists());
+		assert!(recent.exists());
+	}
+
+	#[test]
+	fn budget_policy_keeps_the_newest_sessions_that_fit() {
+		let dir = tempfile::tempdir().unwrap();
+		let oldest = make_session(dir.path(), "100", 400);
+		let middle = make_session(dir.path(), "200", 300);
+		let newest = make_session(dir.path(), "300", 300);
+		// Nested files count towards the session size
+		std::fs::create_dir(middle.join("extra")).unwrap();
+		std::fs::write(middle.join("extra").join("wide@640x360.png"), [0u8; 100]).unwrap();
+
+		assert_eq!(
+			plan_cleanup(dir.path(), RetainPolicy::Budget(700)),
+			std::slice::from_ref(&oldest)
+		);
+		assert_eq!(
+			plan_cleanup(dir.path(), RetainPolicy::Budget(699)),
+			[middle.clone(), oldest.clone()]
+		);
+
+		cleanup_sessions_to_budget(dir.path(), 700);
+		assert!(!oldest.exists());
+		assert!(middle.exists());
+		assert!(newest.exists());
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn budget_policy_skips_unreadable_entries() {
+		use std::os::unix::fs::{PermissionsExt, symlink};
+
+		let dir = tempfile::tempdir().unwrap();
+		let oldest = make_session(dir.path(), "100", 100);
+		let newest = make_session(dir.path(), "200", 100);
+		symlink(dir.path().join("missing"), newest.join("dangling.png")).unwrap();
+		let locked = newest.join("locked");
+		std::fs::create_dir(&locked).unwrap();
+		std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+		let planned = plan_cleanup(dir.path(), RetainPolicy::Budget(150));
+		std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+		assert_eq!(planned, [oldest]);
+		assert_eq!(dir_size(&dir.path().join("missing")), 0);
+	}
+
+	#[test]
+	fn dry_run_plan_leaves_every_session_in_place() {
+		let dir = tempfile::tempdir().unwrap();
+		let sessions: Vec<_> = (1..=4)
+			.map(|i| make_session(dir.path(), &(i * 100).to_string(), 10))
+			.collect();
+
+		let planned = plan_cleanup(dir.path(), RetainPolicy::Count(1));
+		assert_eq!(planned.len(), 3);
+		for session in &sessions {
+			assert!(session.exists(), "{} was removed", session.display());
+		}
+
+		assert_eq!(
+			cleanup_sessions(dir.path(), RetainPolicy::Count(1)),
+			planned
+		);
+		assert!(planned.iter().all(|path| !path.exists()));
+	}
+
+	#[test]
+	fn labeled_sessions_sort_by_timestamp_prefix() {
+		let dir = tempfile::tempdir().unwrap();
+		let oldest = make_session(dir.path(), "100_baseline", 1);
+		let plain = make_session(dir.path(), "200", 1);
+		let newest = make_session(dir.path(), "300_after_fix", 1);
+		let unrelated = make_session(dir.path(), "golden", 1);
+		let not_a_timestamp = make_session(dir.path(), "v2_100", 1);
+
+		let found: Vec<_> = sessions(dir.path()).into_iter().map(|(t, _)| t).collect();
+		assert_eq!(found, [300, 200, 100]);
+
+		assert_eq!(
+			cleanup_sessions(dir.path(), RetainPolicy::Count(2)),
+			std::slice::from_ref(&oldest)
+		);
+		assert!(!oldest.exists());
+		for kept in [plain, newest, unrelated, not_a_timestamp] {
+			assert!(kept.exists(), "{} was removed", kept.display());
+		}
 	}
 }
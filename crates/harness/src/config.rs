@@ -1,11 +1,23 @@
 //! Screenshot configuration with CLI argument support.
 
+use std::collections::HashSet;
+use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::prelude::*;
-use clap::Parser;
+use clap::builder::NonEmptyStringValueParser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use image::codecs::png::CompressionType;
 
-use crate::presets::{CameraPreset, DETAIL_PRESETS, SIMPLE_PRESETS, STANDARD_PRESETS};
+use crate::cleanup::RetainPolicy;
+use crate::headless::GraphicsBackend;
+use crate::presets::{
+	CameraPreset, DETAIL_PRESETS, SIMPLE_PRESETS, STANDARD_PRESETS, load_presets,
+};
+use crate::state::{PRE_ROLL_FRAMES, SETTLE_FRAMES};
 
 /// Default screenshot output directory
 pub const DEFAULT_OUTPUT_DIR: &str = "output";
@@ -16,6 +28,112 @@ pub const SCREENSHOT_WIDTH: u32 = 1920;
 /// Screenshot image height
 pub const SCREENSHOT_HEIGHT: u32 = 1080;
 
+/// Frames to wait for tracked assets before capturing anyway
+pub const ASSET_TIMEOUT_FRAMES: u32 = 600;
+
+/// Default number of frames a capture sequence may overrun its expected length
+/// before it is aborted
+pub const MAX_FRAMES: u32 = 2000;
+
+/// Multiplier applied to the frame limit when rendering with `--force-software`
+pub const SOFTWARE_FRAME_FACTOR: u32 = 4;
+
+/// Instructions logged after the capture summary in agent mode, unless overridden
+pub const DEFAULT_AGENT_INSTRUCTIONS: &str =
+	"Agents: Read screenshots ONE AT A TIME. Analyze before proceeding.";
+
+/// Default capture file stem: the bare preset name
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{preset}";
+
+/// How presets are mapped onto cameras
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureMode {
+	/// One camera visits each preset in turn, settling between shots
+	#[default]
+	Sequential,
+	/// One camera and render target per preset, all captured in the same frame.
+	///
+	/// Faster, but allocates a full-resolution render target for every preset.
+	Simultaneous,
+}
+
+/// Lights the harness spawns for captures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightingConfig {
+	/// Direction the directional light shines in
+	pub direction: Vec3,
+	/// Directional light illuminance in lux
+	pub illuminance: f32,
+	/// Whether the directional light casts shadows
+	pub shadows: bool,
+	/// Color of the global ambient light
+	pub ambient_color: Color,
+	/// Global ambient light brightness in cd/m²
+	pub ambient_brightness: f32,
+}
+
+impl Default for LightingConfig {
+	fn default() -> Self {
+		Self {
+			direction: Quat::from_euler(EulerRot::XYZ, -0.8, 0.4, 0.0) * Vec3::NEG_Z,
+			illuminance: 10000.0,
+			shadows: true,
+			ambient_color: Color::WHITE,
+			ambient_brightness: 300.0,
+		}
+	}
+}
+
+/// Color grading applied to captures on the CPU before saving.
+///
+/// Operates on the encoded sRGB values: exposure scales, contrast pivots around
+/// mid-grey, saturation blends towards Rec. 709 luma, and tint multiplies. Alpha is
+/// left untouched. The default is the identity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorGrade {
+	/// Exposure adjustment in stops
+	pub exposure: f32,
+	pub contrast: f32,
+	pub saturation: f32,
+	pub tint: Color,
+}
+
+impl Default for ColorGrade {
+	fn default() -> Self {
+		Self {
+			exposure: 0.0,
+			contrast: 1.0,
+			saturation: 1.0,
+			tint: Color::WHITE,
+		}
+	}
+}
+
+/// Encoding of saved screenshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+	/// PNG at the given DEFLATE compression level
+	Png(CompressionType),
+	/// JPEG at the given quality (1-100); the alpha channel is dropped
+	Jpeg(u8),
+}
+
+impl OutputFormat {
+	/// File extension for this format, without the dot
+	pub fn extension(&self) -> &'static str {
+		match self {
+			Self::Png(_) => "png",
+			Self::Jpeg(_) => "jpg",
+		}
+	}
+}
+
+impl Default for OutputFormat {
+	fn default() -> Self {
+		Self::Png(CompressionType::default())
+	}
+}
+
 /// CLI arguments for screenshot configuration
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about = "Screenshot harness for Bevy examples")]
@@ -24,6 +142,14 @@ pub struct CliArgs {
 	#[arg(long, short = 'i')]
 	pub interactive: bool,
 
+	/// Print the capture summary as JSON with instructions for automated agents
+	#[arg(long)]
+	pub agent_mode: bool,
+
+	/// Show the FPS/frame time overlay in interactive mode (toggle with F3)
+	#[arg(long)]
+	pub diagnostics: bool,
+
 	/// Output directory for screenshots
 	#[arg(long, short = 'o', default_value = DEFAULT_OUTPUT_DIR)]
 	pub output_dir: PathBuf,
@@ -47,6 +173,71 @@ pub struct CliArgs {
 	/// Number of recent sessions to retain
 	#[arg(long, default_value_t = 5)]
 	pub retain_sessions: usize,
+
+	/// Retain sessions younger than this many seconds instead of a fixed count
+	#[arg(
+		long = "retain-age",
+		value_name = "SECONDS",
+		conflicts_with = "retain_max_bytes"
+	)]
+	pub retain_age_secs: Option<u64>,
+
+	/// Retain the newest sessions that fit within this many bytes instead of a fixed count
+	#[arg(long = "retain-bytes", value_name = "BYTES")]
+	pub retain_max_bytes: Option<u64>,
+
+	/// Label appended to the session directory name (`{timestamp}_{label}`)
+	#[arg(long = "label")]
+	pub session_label: Option<String>,
+
+	/// Fixed session directory name (e.g. `latest`), replaced on every run
+	#[arg(
+		long = "session",
+		conflicts_with = "session_label",
+		value_parser = NonEmptyStringValueParser::new()
+	)]
+	pub session_name: Option<String>,
+
+	/// Log which old sessions cleanup would remove instead of deleting them
+	#[arg(long)]
+	pub cleanup_dry_run: bool,
+
+	/// Frames to wait before the first capture
+	#[arg(long = "pre-roll", default_value_t = PRE_ROLL_FRAMES)]
+	pub pre_roll_frames: u32,
+
+	/// Frames to wait between captures
+	#[arg(long = "settle", default_value_t = SETTLE_FRAMES)]
+	pub settle_frames: u32,
+
+	/// Graphics API to render with
+	#[arg(long, value_enum)]
+	pub backend: Option<GraphicsBackend>,
+
+	/// Use the first adapter whose name contains this text (case-insensitive)
+	#[arg(long)]
+	pub adapter: Option<String>,
+
+	/// Render with a software adapter (e.g. Lavapipe/llvmpipe) for machines without a GPU.
+	///
+	/// Expect frames to take tens to hundreds of milliseconds at full resolution, and
+	/// output that differs slightly from hardware (filtering, precision, MSAA resolve).
+	/// The `--max-frames` limit is multiplied by 4 to allow for the slower frames.
+	#[arg(long)]
+	pub force_software: bool,
+
+	/// Abort with an error if capture runs this many frames past its expected length,
+	/// which grows with the preset count and settle frames (0 disables)
+	#[arg(long, default_value_t = MAX_FRAMES)]
+	pub max_frames: u32,
+
+	/// RON or JSON file of camera presets, replacing the example's defaults
+	#[arg(long)]
+	pub presets_file: Option<PathBuf>,
+
+	/// Capture only the preset with this name
+	#[arg(long = "preset")]
+	pub preset_filter: Option<String>,
 }
 
 impl CliArgs {
@@ -62,12 +253,27 @@ impl Default for CliArgs {
 	fn default() -> Self {
 		Self {
 			interactive: false,
+			agent_mode: false,
+			diagnostics: false,
 			output_dir: PathBuf::from(DEFAULT_OUTPUT_DIR),
 			width: SCREENSHOT_WIDTH,
 			height: SCREENSHOT_HEIGHT,
 			exit_after: true,
 			multi_shot: true,
 			retain_sessions: 5,
+			retain_age_secs: None,
+			retain_max_bytes: None,
+			session_label: None,
+			session_name: None,
+			cleanup_dry_run: false,
+			pre_roll_frames: PRE_ROLL_FRAMES,
+			settle_frames: SETTLE_FRAMES,
+			backend: None,
+			adapter: None,
+			force_software: false,
+			max_frames: MAX_FRAMES,
+			presets_file: None,
+			preset_filter: None,
 		}
 	}
 }
@@ -81,6 +287,10 @@ pub struct ScreenshotConfig {
 	pub multi_shot: bool,
 	/// Camera presets to use
 	pub presets: Vec<CameraPreset>,
+	/// File the presets were loaded from; when set, the preset builders leave them untouched
+	pub presets_file: Option<PathBuf>,
+	/// Name of the only preset to capture, applied when the harness plugin is built
+	pub preset_filter: Option<String>,
 	/// Example name (determines screenshot location)
 	pub example_name: String,
 	/// Screenshot width
@@ -89,15 +299,236 @@ pub struct ScreenshotConfig {
 	pub height: u32,
 	/// Output directory
 	pub output_dir: PathBuf,
-	/// Number of sessions to retain
-	pub retain_sessions: usize,
+	/// Which previous sessions to keep when a new one starts
+	pub retain_policy: RetainPolicy,
+	/// Log the sessions cleanup would remove instead of deleting them
+	pub cleanup_dry_run: bool,
+	/// Label appended to the session directory name (`{timestamp}_{label}`)
+	pub session_label: Option<String>,
+	/// Fixed session directory name used instead of the timestamp; cleared before capture
+	pub session_name: Option<String>,
+	/// Active wave count recorded in the session manifest
+	pub wave_count: Option<u32>,
+	/// Frames to wait before the first capture
+	pub pre_roll_frames: u32,
+	/// Frames to wait between captures
+	pub settle_frames: u32,
+	/// Stop settling early once consecutive frames differ by less than this mean
+	/// absolute per-byte difference (0-255); `settle_frames` remains the cap
+	pub settle_threshold: Option<f32>,
+	/// Hold the first capture until all `TrackLoading` assets have loaded
+	pub wait_for_assets: bool,
+	/// Frames to wait for tracked assets before giving up
+	pub asset_timeout_frames: u32,
+	/// Abort with `AppExit::Error` once the sequence runs this many frames past
+	/// [`ScreenshotConfig::expected_frames`]; 0 disables the watchdog
+	pub max_frames: u32,
+	/// Whether presets are captured one after another or all at once
+	pub capture_mode: CaptureMode,
+	/// Multisample anti-aliasing applied to the capture cameras
+	pub msaa: Msaa,
+	/// Tonemapping applied to the capture cameras.
+	///
+	/// Defaults to `TonyMcMapface`, matching a plain `Camera3d` in interactive mode.
+	/// `TonyMcMapface`, `AgX` and `BlenderFilmic` need Bevy's `tonemapping_luts` cargo
+	/// feature (on by default); `headless_plugins` includes the required
+	/// `TonemappingPlugin` through `DefaultPlugins`, so no extra plugins are needed.
+	pub tonemapping: Tonemapping,
+	/// Dither the tonemapped output to reduce color banding
+	pub deband_dither: bool,
+	/// Render at this multiple of the output resolution and downsample on the CPU
+	pub supersample: u32,
+	/// Background color of the captures; zero alpha leaves the saved PNG transparent
+	pub clear_color: Color,
+	/// Pixel region of the output image to keep, in output (not supersampled) pixels
+	pub capture_region: Option<URect>,
+	/// Tile all captures into a labeled contact sheet when the session completes
+	pub contact_sheet: bool,
+	/// Also capture each preset with a wireframe overlay, saved as `{preset}_wire.png`
+	pub capture_wireframe: bool,
+	/// Write a `cameras.json` with each captured preset's transform and projection
+	pub camera_poses: bool,
+	/// Encoding of the saved screenshots
+	pub output_format: OutputFormat,
+	/// World XZ rectangle captured top-down into `wave_map.png` after the presets
+	pub wave_map: Option<Rect>,
+	/// Report captures as JSON on stdout with agent instructions, instead of a log table
+	pub agent_mode: bool,
+	/// Guidance logged once the session completes; `None` logs nothing
+	pub agent_instructions: Option<String>,
+	/// Additional `(width, height)` sizes each capture is downscaled to on the CPU
+	pub extra_resolutions: Vec<(u32, u32)>,
+	/// Directional and ambient light spawned by `setup_camera`; `None` leaves lighting
+	/// entirely to the example
+	pub lighting: Option<LightingConfig>,
+	/// File stem of each capture; see [`ScreenshotConfig::with_filename_template`]
+	pub filename_template: String,
+	/// Flip captures upside down before any other processing
+	pub flip_vertical: bool,
+	/// Near clip plane of perspective presets
+	pub near: f32,
+	/// Far clip plane of perspective presets
+	pub far: f32,
+	/// Color grading applied to each capture before saving
+	pub color_grade: ColorGrade,
 }
 
+/// Invalid [`ScreenshotConfig`] settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+	/// Width or height is zero
+	ZeroResolution { width: u32, height: u32 },
+	/// The preset list is empty
+	NoPresets,
+	/// Two presets share a name, so their captures would overwrite each other
+	DuplicatePresetName(String),
+	/// The filename template has neither `{preset}` nor `{index}`
+	FilenameTemplate(String),
+	/// A wave map is requested, but sequential single-shot mode only captures the first preset
+	WaveMapSingleShot,
+	/// The fixed session name is empty, which would name the screenshots directory itself
+	EmptySessionName,
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::ZeroResolution { width, height } => {
+				write!(f, "resolution {width}x{height} must be non-zero")
+			}
+			Self::NoPresets => write!(f, "no camera presets to capture"),
+			Self::DuplicatePresetName(name) => {
+				write!(f, "more than one camera preset is named '{name}'")
+			}
+			Self::FilenameTemplate(template) => write!(
+				f,
+				"filename template '{template}' must contain {{preset}} or {{index}}"
+			),
+			Self::EmptySessionName => write!(f, "session name must not be empty"),
+			Self::WaveMapSingleShot => write!(
+				f,
+				"the wave map is never captured in sequential single-shot mode"
+			),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
+
 impl ScreenshotConfig {
 	/// Returns the base directory for screenshots: {output_dir}/{example_name}/screenshots
 	pub fn screenshot_dir(&self) -> PathBuf {
 		self.output_dir.join(&self.example_name).join("screenshots")
 	}
+
+	/// Checks for settings that would fail or silently lose captures later on
+	pub fn validate(&self) -> Result<(), ConfigError> {
+		if self.width == 0 || self.height == 0 {
+			return Err(ConfigError::ZeroResolution {
+				width: self.width,
+				height: self.height,
+			});
+		}
+
+		if self.presets.is_empty() {
+			return Err(ConfigError::NoPresets);
+		}
+
+		let mut names = HashSet::new();
+		if let Some(duplicate) = self
+			.presets
+			.iter()
+			.find(|preset| !names.insert(preset.name.as_ref()))
+		{
+			return Err(ConfigError::DuplicatePresetName(duplicate.name.to_string()));
+		}
+
+		if !self.filename_template.contains("{preset}")
+			&& !self.filename_template.contains("{index}")
+		{
+			return Err(ConfigError::FilenameTemplate(
+				self.filename_template.clone(),
+			));
+		}
+
+		if self.session_name.as_deref() == Some("") {
+			return Err(ConfigError::EmptySessionName);
+		}
+
+		if self.wave_map.is_some()
+			&& self.capture_mode == CaptureMode::Sequential
+			&& !self.multi_shot
+		{
+			return Err(ConfigError::WaveMapSingleShot);
+		}
+
+		Ok(())
+	}
+
+	/// Finishes the builder chain, returning the config if [`Self::validate`] passes
+	pub fn build(self) -> Result<Self, ConfigError> {
+		self.validate()?;
+		Ok(self)
+	}
+
+	/// Expands [`ScreenshotConfig::filename_template`] for the preset at `index`
+	pub fn capture_file_stem(&self, preset: &str, index: usize, session: &str) -> String {
+		self.filename_template
+			.replace("{example}", &self.example_name)
+			.replace("{preset}", preset)
+			.replace("{width}", &self.width.to_string())
+			.replace("{height}", &self.height.to_string())
+			.replace("{index}", &format!("{index:02}"))
+			.replace("{session}", session)
+	}
+
+	/// Projection of the camera placed for `preset`, with the configured clip planes
+	/// applied to perspective presets
+	pub fn projection(&self, preset: &CameraPreset) -> Projection {
+		match preset.projection() {
+			Projection::Perspective(perspective) => {
+				Projection::Perspective(PerspectiveProjection {
+					near: self.near,
+					far: self.far,
+					..perspective
+				})
+			}
+			projection => projection,
+		}
+	}
+
+	/// Settle frames before capturing `preset`: its own override, or the config default
+	pub fn preset_settle_frames(&self, preset: &CameraPreset) -> u32 {
+		preset.settle_frames.unwrap_or(self.settle_frames)
+	}
+
+	/// Frames a healthy sequence needs: the pre-roll, the asset wait if enabled, and
+	/// each capture's settle frames plus the capture frame, twice with a wireframe pass.
+	pub fn expected_frames(&self) -> u32 {
+		let settle = |preset: &CameraPreset| self.preset_settle_frames(preset);
+		let captures: u32 = match self.capture_mode {
+			CaptureMode::Sequential if self.multi_shot => {
+				self.presets.iter().map(|preset| settle(preset) + 1).sum()
+			}
+			CaptureMode::Sequential => self.presets.first().map_or(0, settle) + 1,
+			CaptureMode::Simultaneous => self.presets.iter().map(settle).max().unwrap_or(0) + 1,
+		};
+		let passes = if self.capture_wireframe { 2 } else { 1 };
+		let asset_wait = if self.wait_for_assets {
+			self.asset_timeout_frames
+		} else {
+			0
+		};
+
+		self.pre_roll_frames
+			.saturating_add(asset_wait)
+			.saturating_add(captures.saturating_mul(passes))
+	}
+
+	/// Size of the render target, including any supersampling
+	pub fn render_size(&self) -> UVec2 {
+		UVec2::new(self.width, self.height) * self.supersample.max(1)
+	}
 }
 
 impl Default for ScreenshotConfig {
@@ -106,11 +537,44 @@ impl Default for ScreenshotConfig {
 			exit_after: true,
 			multi_shot: true,
 			presets: STANDARD_PRESETS.to_vec(),
+			presets_file: None,
+			preset_filter: None,
 			example_name: "default".to_string(),
 			width: SCREENSHOT_WIDTH,
 			height: SCREENSHOT_HEIGHT,
 			output_dir: PathBuf::from(DEFAULT_OUTPUT_DIR),
-			retain_sessions: 5,
+			retain_policy: RetainPolicy::default(),
+			cleanup_dry_run: false,
+			session_label: None,
+			session_name: None,
+			wave_count: None,
+			pre_roll_frames: PRE_ROLL_FRAMES,
+			settle_frames: SETTLE_FRAMES,
+			settle_threshold: None,
+			wait_for_assets: false,
+			asset_timeout_frames: ASSET_TIMEOUT_FRAMES,
+			max_frames: MAX_FRAMES,
+			capture_mode: CaptureMode::Sequential,
+			msaa: Msaa::default(),
+			tonemapping: Tonemapping::TonyMcMapface,
+			deband_dither: true,
+			supersample: 1,
+			clear_color: Color::BLACK,
+			capture_region: None,
+			contact_sheet: false,
+			capture_wireframe: false,
+			camera_poses: false,
+			output_format: OutputFormat::default(),
+			wave_map: None,
+			agent_mode: false,
+			agent_instructions: None,
+			extra_resolutions: Vec::new(),
+			lighting: Some(LightingConfig::default()),
+			filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+			flip_vertical: false,
+			near: PerspectiveProjection::default().near,
+			far: PerspectiveProjection::default().far,
+			color_grade: ColorGrade::default(),
 		}
 	}
 }
@@ -120,15 +584,70 @@ impl ScreenshotConfig {
 	pub fn from_cli(example_name: &str) -> Self {
 		let args = CliArgs::parse();
 
+		let presets = match &args.presets_file {
+			Some(path) => load_presets(path).unwrap_or_else(|err| {
+				CliArgs::command()
+					.error(
+						ErrorKind::InvalidValue,
+						format!("{}: {err}", path.display()),
+					)
+					.exit()
+			}),
+			None => STANDARD_PRESETS.to_vec(),
+		};
+
 		Self {
 			exit_after: args.exit_after,
 			multi_shot: args.multi_shot,
-			presets: STANDARD_PRESETS.to_vec(),
+			presets,
+			presets_file: args.presets_file,
+			preset_filter: args.preset_filter,
 			example_name: example_name.to_string(),
 			width: args.width,
 			height: args.height,
 			output_dir: args.output_dir,
-			retain_sessions: args.retain_sessions,
+			retain_policy: match (args.retain_age_secs, args.retain_max_bytes) {
+				(Some(secs), _) => RetainPolicy::Age(Duration::from_secs(secs)),
+				(_, Some(bytes)) => RetainPolicy::Budget(bytes),
+				(None, None) => RetainPolicy::Count(args.retain_sessions),
+			},
+			cleanup_dry_run: args.cleanup_dry_run,
+			session_label: args.session_label,
+			session_name: args.session_name,
+			wave_count: None,
+			pre_roll_frames: args.pre_roll_frames,
+			settle_frames: args.settle_frames,
+			settle_threshold: None,
+			wait_for_assets: false,
+			asset_timeout_frames: ASSET_TIMEOUT_FRAMES,
+			max_frames: if args.force_software {
+				args.max_frames.saturating_mul(SOFTWARE_FRAME_FACTOR)
+			} else {
+				args.max_frames
+			},
+			capture_mode: CaptureMode::Sequential,
+			msaa: Msaa::default(),
+			tonemapping: Tonemapping::TonyMcMapface,
+			deband_dither: true,
+			supersample: 1,
+			clear_color: Color::BLACK,
+			capture_region: None,
+			contact_sheet: false,
+			capture_wireframe: false,
+			camera_poses: false,
+			output_format: OutputFormat::default(),
+			wave_map: None,
+			agent_mode: args.agent_mode,
+			agent_instructions: args
+				.agent_mode
+				.then(|| DEFAULT_AGENT_INSTRUCTIONS.to_string()),
+			extra_resolutions: Vec::new(),
+			lighting: Some(LightingConfig::default()),
+			filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+			flip_vertical: false,
+			near: PerspectiveProjection::default().near,
+			far: PerspectiveProjection::default().far,
+			color_grade: ColorGrade::default(),
 		}
 	}
 
@@ -145,38 +664,383 @@ impl ScreenshotConfig {
 			exit_after,
 			multi_shot,
 			presets: STANDARD_PRESETS.to_vec(),
+			presets_file: None,
+			preset_filter: None,
 			example_name: example_name.to_string(),
 			width: SCREENSHOT_WIDTH,
 			height: SCREENSHOT_HEIGHT,
 			output_dir: PathBuf::from(DEFAULT_OUTPUT_DIR),
-			retain_sessions: 5,
+			retain_policy: RetainPolicy::default(),
+			cleanup_dry_run: false,
+			session_label: None,
+			session_name: None,
+			wave_count: None,
+			pre_roll_frames: PRE_ROLL_FRAMES,
+			settle_frames: SETTLE_FRAMES,
+			settle_threshold: None,
+			wait_for_assets: false,
+			asset_timeout_frames: ASSET_TIMEOUT_FRAMES,
+			max_frames: MAX_FRAMES,
+			capture_mode: CaptureMode::Sequential,
+			msaa: Msaa::default(),
+			tonemapping: Tonemapping::TonyMcMapface,
+			deband_dither: true,
+			supersample: 1,
+			clear_color: Color::BLACK,
+			capture_region: None,
+			contact_sheet: false,
+			capture_wireframe: false,
+			camera_poses: false,
+			output_format: OutputFormat::default(),
+			wave_map: None,
+			agent_mode: false,
+			agent_instructions: None,
+			extra_resolutions: Vec::new(),
+			lighting: Some(LightingConfig::default()),
+			filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+			flip_vertical: false,
+			near: PerspectiveProjection::default().near,
+			far: PerspectiveProjection::default().far,
+			color_grade: ColorGrade::default(),
 		}
 	}
 
 	/// Use detail presets instead of standard ones
-	pub fn with_detail_presets(mut self) -> Self {
-		self.presets = DETAIL_PRESETS.to_vec();
-		self
+	pub fn with_detail_presets(self) -> Self {
+		self.with_presets(DETAIL_PRESETS.to_vec())
 	}
 
 	/// Use simple presets for basic examples
-	pub fn with_simple_presets(mut self) -> Self {
-		self.presets = SIMPLE_PRESETS.to_vec();
-		self
+	pub fn with_simple_presets(self) -> Self {
+		self.with_presets(SIMPLE_PRESETS.to_vec())
 	}
 
-	/// Use custom presets
+	/// Use custom presets.
+	///
+	/// Ignored when presets were loaded from `--presets-file`, so the file always
+	/// overrides the example's built-in list. Preset names must be unique; generated
+	/// presets need distinct names such as `orbit_0`, `orbit_1`, ...
+	/// (see [`CameraPreset::orbit_ring`]).
 	pub fn with_presets(mut self, presets: Vec<CameraPreset>) -> Self {
-		self.presets = presets;
+		if self.presets_file.is_none() {
+			self.presets = presets;
+		}
 		self
 	}
 
+	/// Set which previous sessions are kept when a new session starts
+	pub fn with_retain_policy(mut self, policy: RetainPolicy) -> Self {
+		self.retain_policy = policy;
+		self
+	}
+
+	/// Log which old sessions cleanup would remove instead of deleting them
+	pub fn with_cleanup_dry_run(mut self) -> Self {
+		self.cleanup_dry_run = true;
+		self
+	}
+
+	/// Append a label to the session directory name
+	pub fn with_session_label(mut self, label: impl Into<String>) -> Self {
+		self.session_label = Some(label.into());
+		self
+	}
+
+	/// Write into a fixed session directory instead of a timestamped one.
+	///
+	/// The directory is cleared at startup and never removed by session cleanup, even
+	/// when the name starts with digits. A name such as `latest` gives downstream tools a
+	/// stable path like `screenshots/latest`.
+	pub fn with_session_name(mut self, name: impl Into<String>) -> Self {
+		self.session_name = Some(name.into());
+		self
+	}
+
+	/// Capture only the preset named `name`
+	pub fn with_preset_filter(mut self, name: impl Into<String>) -> Self {
+		self.preset_filter = Some(name.into());
+		self
+	}
+
+	/// Narrows `presets` to the one matching `preset_filter`, if set.
+	///
+	/// Exits with a CLI error listing the available names when no preset matches.
+	pub(crate) fn apply_preset_filter(&mut self) {
+		let Some(name) = &self.preset_filter else {
+			return;
+		};
+
+		match self
+			.presets
+			.iter()
+			.find(|preset| preset.name == name.as_str())
+		{
+			Some(preset) => self.presets = vec![preset.clone()],
+			None => {
+				let available: Vec<&str> = self.presets.iter().map(|p| p.name.as_ref()).collect();
+				CliArgs::command()
+					.error(
+						ErrorKind::InvalidValue,
+						format!(
+							"no camera preset named '{name}' (available: {})",
+							available.join(", ")
+						),
+					)
+					.exit()
+			}
+		}
+	}
+
 	/// Don't exit after screenshots
 	pub fn no_exit(mut self) -> Self {
 		self.exit_after = false;
 		self
 	}
 
+	/// Set the number of frames to wait before the first capture
+	pub fn with_pre_roll_frames(mut self, frames: u32) -> Self {
+		self.pre_roll_frames = frames;
+		self
+	}
+
+	/// Set the number of frames to wait between captures
+	pub fn with_settle_frames(mut self, frames: u32) -> Self {
+		self.settle_frames = frames;
+		self
+	}
+
+	/// Settle adaptively: move on to the next capture as soon as two consecutive frames
+	/// differ by less than `threshold` (mean absolute difference per byte, 0-255), or
+	/// after `max_frames` frames.
+	///
+	/// Continuously animated scenes never settle and always wait the full `max_frames`.
+	pub fn with_adaptive_settle(mut self, threshold: f32, max_frames: u32) -> Self {
+		self.settle_threshold = Some(threshold);
+		self.settle_frames = max_frames;
+		self
+	}
+
+	/// Wait for assets marked with `TrackLoading` before the first capture.
+	///
+	/// Gives up after `timeout_frames` and captures anyway, logging a warning.
+	pub fn wait_for_assets(mut self, timeout_frames: u32) -> Self {
+		self.wait_for_assets = true;
+		self.asset_timeout_frames = timeout_frames;
+		self
+	}
+
+	/// Render every preset from its own camera and capture them in a single frame
+	pub fn with_simultaneous_capture(mut self) -> Self {
+		self.capture_mode = CaptureMode::Simultaneous;
+		self
+	}
+
+	/// Set the MSAA sample count (1, 2, 4 or 8) for the capture cameras.
+	///
+	/// Bevy renders into an internal multisampled texture and resolves it into the
+	/// single-sampled render target, so the readback always sees the resolved image.
+	///
+	/// # Panics
+	/// Panics if `samples` is not a supported sample count.
+	pub fn with_msaa(mut self, samples: u32) -> Self {
+		self.msaa = Msaa::from_samples(samples);
+		self
+	}
+
+	/// Render at `factor` times the output resolution and box-downsample before saving.
+	///
+	/// Anti-aliases everything, including shader aliasing MSAA can't reach. The render
+	/// target is `factor²` times larger, so keep `width * factor` within the GPU's
+	/// maximum texture dimension (commonly 8192).
+	pub fn with_supersample(mut self, factor: u32) -> Self {
+		self.supersample = factor.max(1);
+		self
+	}
+
+	/// Set the background color of the captures
+	pub fn with_clear_color(mut self, color: impl Into<Color>) -> Self {
+		self.clear_color = color.into();
+		self
+	}
+
+	/// Clear to fully transparent instead of opaque black.
+	///
+	/// The alpha channel of the saved PNG then marks where geometry was drawn,
+	/// which is useful for compositing captures over other images.
+	pub fn with_transparent_background(self) -> Self {
+		self.with_clear_color(Color::NONE)
+	}
+
+	/// Abort the run if capture overruns its expected length by `frames` frames (0 disables)
+	pub fn with_max_frames(mut self, frames: u32) -> Self {
+		self.max_frames = frames;
+		self
+	}
+
+	/// Crop saved screenshots to `region`, given in output pixels.
+	///
+	/// The region is clamped to the image bounds; if nothing remains the full frame is saved.
+	pub fn with_capture_region(mut self, region: URect) -> Self {
+		self.capture_region = Some(region);
+		self
+	}
+
+	/// Write a `contact_sheet.png` grid of all captures, labeled by preset name
+	pub fn with_contact_sheet(mut self) -> Self {
+		self.contact_sheet = true;
+		self
+	}
+
+	/// Also capture each preset with a wireframe overlay.
+	///
+	/// Adds Bevy's `WireframePlugin`, which needs the `POLYGON_MODE_LINE` GPU feature.
+	pub fn with_wireframe_capture(mut self) -> Self {
+		self.capture_wireframe = true;
+		self
+	}
+
+	/// Write a `cameras.json` of the captured camera poses for re-rendering elsewhere
+	pub fn with_camera_poses(mut self) -> Self {
+		self.camera_poses = true;
+		self
+	}
+
+	/// Save PNGs at the given compression level, trading file size for write speed
+	pub fn with_png_compression(mut self, compression: CompressionType) -> Self {
+		self.output_format = OutputFormat::Png(compression);
+		self
+	}
+
+	/// Save JPEGs at `quality` (1-100) instead of PNGs
+	pub fn with_jpeg_quality(mut self, quality: u8) -> Self {
+		self.output_format = OutputFormat::Jpeg(quality.clamp(1, 100));
+		self
+	}
+
+	/// Set the tonemapping operator used by the capture cameras
+	pub fn with_tonemapping(mut self, tonemapping: Tonemapping) -> Self {
+		self.tonemapping = tonemapping;
+		self
+	}
+
+	/// Disable deband dithering, e.g. for bit-exact comparisons between captures
+	pub fn without_deband_dither(mut self) -> Self {
+		self.deband_dither = false;
+		self
+	}
+
+	/// Also capture an orthographic top-down `wave_map` image of the world XZ rectangle
+	/// `area` (`x` is world X, `y` is world Z).
+	///
+	/// The session manifest records the world-to-pixel mapping. Keep the rectangle's
+	/// aspect ratio equal to the output resolution's for square pixels. The image is saved
+	/// as `wave_map` without the vertical flip, capture region or filename template, so the
+	/// mapping holds.
+	///
+	/// Sequential single-shot mode stops after the first preset, so it fails validation
+	/// with a wave map.
+	pub fn with_wave_map(mut self, area: Rect) -> Self {
+		self.wave_map = Some(area);
+		self
+	}
+
+	/// Report captures as JSON on stdout for automated agents.
+	///
+	/// Also sets the [`DEFAULT_AGENT_INSTRUCTIONS`] unless instructions were already set.
+	pub fn with_agent_mode(mut self) -> Self {
+		self.agent_mode = true;
+		self.agent_instructions
+			.get_or_insert_with(|| DEFAULT_AGENT_INSTRUCTIONS.to_string());
+		self
+	}
+
+	/// Log `instructions` once the session completes, replacing the agent mode default
+	pub fn with_agent_instructions(mut self, instructions: impl Into<String>) -> Self {
+		self.agent_instructions = Some(instructions.into());
+		self
+	}
+
+	/// Don't log any instructions when the session completes, even in agent mode
+	pub fn without_agent_instructions(mut self) -> Self {
+		self.agent_instructions = None;
+		self
+	}
+
+	/// Also save each capture scaled to these sizes as `{preset}@{w}x{h}`.
+	///
+	/// The copies are resampled from the primary capture after it is saved, so nothing
+	/// is re-rendered and the primary image is unchanged.
+	pub fn with_extra_resolutions(mut self, resolutions: Vec<(u32, u32)>) -> Self {
+		self.extra_resolutions = resolutions
+			.into_iter()
+			.filter(|&(width, height)| width > 0 && height > 0)
+			.collect();
+		self
+	}
+
+	/// Set the directional and ambient light used for captures
+	pub fn with_lighting(mut self, lighting: LightingConfig) -> Self {
+		self.lighting = Some(lighting);
+		self
+	}
+
+	/// Render the harness's directional light without shadows.
+	///
+	/// Skips the shadow map passes, which often dominate frame time on dense meshes.
+	/// Has no effect on lights the example spawns itself.
+	pub fn no_shadows(mut self) -> Self {
+		if let Some(lighting) = &mut self.lighting {
+			lighting.shadows = false;
+		}
+		self
+	}
+
+	/// Don't spawn the harness's directional light or set `GlobalAmbientLight`.
+	///
+	/// Use this when the example spawns its own lights or environment map, so the scene
+	/// isn't lit twice. Without an example-provided `GlobalAmbientLight`, Bevy's default
+	/// ambient level applies.
+	pub fn without_default_lighting(mut self) -> Self {
+		self.lighting = None;
+		self
+	}
+
+	/// Name captures after `template` instead of the bare preset name.
+	///
+	/// `{example}`, `{preset}`, `{width}`, `{height}`, `{index}` (the preset's position,
+	/// zero-padded to two digits) and `{session}` are substituted; the extension and any
+	/// `_wire` suffix are appended afterwards.
+	///
+	/// The template must contain `{preset}` or `{index}`, since every capture would
+	/// otherwise overwrite the previous one; [`Self::validate`] reports it if not.
+	pub fn with_filename_template(mut self, template: impl Into<String>) -> Self {
+		self.filename_template = template.into();
+		self
+	}
+
+	/// Flip captures vertically, for render targets whose origin is at the bottom
+	pub fn with_vertical_flip(mut self) -> Self {
+		self.flip_vertical = true;
+		self
+	}
+
+	/// Set the near and far clip planes of perspective presets.
+	///
+	/// Raise `far` when the scene extends past Bevy's default of 1000 units, or the
+	/// horizon is clipped. Anything that unprojects through the camera's projection,
+	/// like a projected-grid mesh, sees the same planes, so its reach changes with `far`.
+	pub fn with_clip_planes(mut self, near: f32, far: f32) -> Self {
+		self.near = near;
+		self.far = far;
+		self
+	}
+
+	/// Color grade every capture before it is saved
+	pub fn with_color_grade(mut self, grade: ColorGrade) -> Self {
+		self.color_grade = grade;
+		self
+	}
+
 	/// Set custom resolution
 	pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
 		self.width = width;
@@ -184,9 +1048,99 @@ impl ScreenshotConfig {
 		self
 	}
 
+	/// Record the scene's active wave count in the session manifest
+	pub fn with_wave_count(mut self, count: u32) -> Self {
+		self.wave_count = Some(count);
+		self
+	}
+
 	/// Set custom output directory
 	pub fn with_output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
 		self.output_dir = dir.into();
 		self
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_config_is_valid() {
+		assert!(ScreenshotConfig::default().build().is_ok());
+	}
+
+	#[test]
+	fn zero_resolution_is_rejected() {
+		for (width, height) in [(0, 720), (1280, 0), (0, 0)] {
+			let result = ScreenshotConfig::default()
+				.with_resolution(width, height)
+				.build();
+			assert_eq!(
+				result.err(),
+				Some(ConfigError::ZeroResolution { width, height })
+			);
+		}
+	}
+
+	#[test]
+	fn empty_preset_list_is_rejected() {
+		let result = ScreenshotConfig::default().with_presets(Vec::new()).build();
+		assert_eq!(result.err(), Some(ConfigError::NoPresets));
+	}
+
+	#[test]
+	fn duplicate_preset_names_are_rejected() {
+		let result = ScreenshotConfig::default()
+			.with_presets(vec![
+				CameraPreset::polar("front", 10.0, 5.0, 0.0),
+				CameraPreset::polar("side", 10.0, 5.0, 1.0),
+				CameraPreset::polar("front", 20.0, 5.0, 2.0),
+			])
+			.build();
+		let err = result.err().unwrap();
+		assert_eq!(err, ConfigError::DuplicatePresetName("front".into()));
+		assert_eq!(
+			err.to_string(),
+			"more than one camera preset is named 'front'"
+		);
+	}
+
+	#[test]
+	fn empty_session_name_is_rejected() {
+		let result = ScreenshotConfig::default().with_session_name("").build();
+		assert_eq!(result.err(), Some(ConfigError::EmptySessionName));
+		assert!(CliArgs::try_parse_from(["harness", "--session", ""]).is_err());
+	}
+
+	#[test]
+	fn filename_template_needs_preset_or_index() {
+		let build = |template: &str| {
+			ScreenshotConfig::default()
+				.with_filename_template(template)
+				.build()
+				.err()
+		};
+		assert_eq!(build("{example}_{preset}"), None);
+		assert_eq!(build("frame_{index}"), None);
+		assert_eq!(
+			build("{example}_{width}x{height}"),
+			Some(ConfigError::FilenameTemplate(
+				"{example}_{width}x{height}".into()
+			))
+		);
+	}
+
+	#[test]
+	fn wave_map_requires_every_preset_in_sequential_mode() {
+		let mut config =
+			ScreenshotConfig::default().with_wave_map(Rect::new(-10.0, -10.0, 10.0, 10.0));
+		assert_eq!(config.validate(), Ok(()));
+
+		config.multi_shot = false;
+		assert_eq!(config.validate(), Err(ConfigError::WaveMapSingleShot));
+
+		config = config.with_simultaneous_capture();
+		assert_eq!(config.validate(), Ok(()));
+	}
+}
@@ -0,0 +1,134 @@
+//! Contact sheet tiling all captured presets into one labeled image.
+
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+
+/// File name of the contact sheet written into the session directory
+pub const CONTACT_SHEET_FILE: &str = "contact_sheet.png";
+
+/// Maximum width of a single cell; larger captures are scaled down to fit
+const MAX_CELL_WIDTH: u32 = 640;
+
+/// Pixel size of one font bitmap dot
+const LABEL_SCALE: u32 = 3;
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+/// Padding around labels and between cells
+const PADDING: u32 = 8;
+
+const BACKGROUND: Rgba<u8> = Rgba([24, 24, 24, 255]);
+const LABEL_COLOR: Rgba<u8> = Rgba([235, 235, 235, 255]);
+
+/// Tiles `cells` into a roughly square grid, labeling each with its name.
+///
+/// All cells are scaled to the size of the first image (capped at
+/// [`MAX_CELL_WIDTH`]). Returns `None` if `cells` is empty.
+pub fn build_contact_sheet(cells: &[(String, RgbaImage)]) -> Option<RgbaImage> {
+	let (_, first) = cells.first()?;
+
+	let cell_width = first.width().min(MAX_CELL_WIDTH);
+	let cell_height = (first.height() as u64 * cell_width as u64 / first.width() as u64) as u32;
+	let label_height = GLYPH_HEIGHT * LABEL_SCALE + 2 * PADDING;
+
+	let columns = (cells.len() as f32).sqrt().ceil() as u32;
+	let rows = (cells.len() as u32).div_ceil(columns);
+
+	let stride_x = cell_width + PADDING;
+	let stride_y = cell_height + label_height;
+	let mut sheet = RgbaImage::from_pixel(
+		columns * stride_x + PADDING,
+		rows * stride_y + PADDING,
+		BACKGROUND,
+	);
+
+	for (index, (name, image)) in cells.iter().enumerate() {
+		let x = PADDING + (index as u32 % columns) * stride_x;
+		let y = PADDING + (index as u32 / columns) * stride_y;
+
+		let thumbnail = imageops::resize(image, cell_width, cell_height, FilterType::Triangle);
+		imageops::overlay(&mut sheet, &thumbnail, x as i64, y as i64);
+		draw_label(&mut sheet, name, x, y + cell_height + PADDING, cell_width);
+	}
+
+	Some(sheet)
+}
+
+/// Draws `text` at (`x`, `y`), truncated to fit `max_width`.
+fn draw_label(sheet: &mut RgbaImage, text: &str, x: u32, y: u32, max_width: u32) {
+	let advance = (GLYPH_WIDTH + 1) * LABEL_SCALE;
+
+	for (index, c) in text.chars().enumerate() {
+		let glyph_x = x + index as u32 * advance;
+		if glyph_x + GLYPH_WIDTH * LABEL_SCALE > x + max_width {
+			break;
+		}
+
+		for (row, bits) in glyph(c).iter().enumerate() {
+			for column in 0..GLYPH_WIDTH {
+				if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+					continue;
+				}
+
+				let px = glyph_x + column * LABEL_SCALE;
+				let py = y + row as u32 * LABEL_SCALE;
+				for dy in 0..LABEL_SCALE {
+					for dx in 0..LABEL_SCALE {
+						if let Some(pixel) = sheet.get_pixel_mut_checked(px + dx, py + dy) {
+							*pixel = LABEL_COLOR;
+						}
+					}
+				}
+			}
+		}
+	}
+}
+
+/// 3×5 bitmap glyphs, one row per entry, most significant bit on the left.
+///
+/// Covers the characters preset names use in practice; anything else renders blank.
+fn glyph(c: char) -> [u8; 5] {
+	match c.to_ascii_lowercase() {
+		'a' => [0b010, 0b101, 0b111, 0b101, 0b101],
+		'b' => [0b110, 0b101, 0b110, 0b101, 0b110],
+		'c' => [0b011, 0b100, 0b100, 0b100, 0b011],
+		'd' => [0b110, 0b101, 0b101, 0b101, 0b110],
+		'e' => [0b111, 0b100, 0b110, 0b100, 0b111],
+		'f' => [0b111, 0b100, 0b110, 0b100, 0b100],
+		'g' => [0b011, 0b100, 0b101, 0b101, 0b011],
+		'h' => [0b101, 0b101, 0b111, 0b101, 0b101],
+		'i' => [0b111, 0b010, 0b010, 0b010, 0b111],
+		'j' => [0b001, 0b001, 0b001, 0b101, 0b010],
+		'k' => [0b101, 0b101, 0b110, 0b101, 0b101],
+		'l' => [0b100, 0b100, 0b100, 0b100, 0b111],
+		'm' => [0b101, 0b111, 0b111, 0b101, 0b101],
+		'n' => [0b110, 0b101, 0b101, 0b101, 0b101],
+		'o' => [0b010, 0b101, 0b101, 0b101, 0b010],
+		'p' => [0b110, 0b101, 0b110, 0b100, 0b100],
+		'q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+		'r' => [0b110, 0b101, 0b110, 0b101, 0b101],
+		's' => [0b011, 0b100, 0b010, 0b001, 0b110],
+		't' => [0b111, 0b010, 0b010, 0b010, 0b010],
+		'u' => [0b101, 0b101, 0b101, 0b101, 0b111],
+		'v' => [0b101, 0b101, 0b101, 0b101, 0b010],
+		'w' => [0b101, 0b101, 0b111, 0b111, 0b101],
+		'x' => [0b101, 0b101, 0b010, 0b101, 0b101],
+		'y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+		'z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+		'0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+		'1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+		'2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+		'3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+		'4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+		'5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+		'6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+		'7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+		'8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+		'9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+		'_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+		'-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+		'.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+		_ => [0; 5],
+	}
+}
@@ -0,0 +1,98 @@
+//! On-screen FPS, frame time and vertex count overlay for interactive mode.
+
+use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+/// Key that shows or hides the diagnostics overlay
+pub const DIAGNOSTICS_TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+/// Plugin that renders FPS, frame time and the visible vertex count as on-screen text.
+///
+/// The overlay can be toggled at runtime with [`DIAGNOSTICS_TOGGLE_KEY`].
+#[derive(Default)]
+pub struct DiagnosticsOverlayPlugin {
+	/// Whether the overlay is shown on startup
+	pub visible: bool,
+}
+
+#[derive(Component)]
+struct DiagnosticsText;
+
+impl Plugin for DiagnosticsOverlayPlugin {
+	fn build(&self, app: &mut App) {
+		if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+			app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+		}
+
+		let visibility = if self.visible {
+			Visibility::Inherited
+		} else {
+			Visibility::Hidden
+		};
+
+		app.add_systems(Startup, move |mut commands: Commands| {
+			commands.spawn((
+				DiagnosticsText,
+				Text::default(),
+				TextFont {
+					font_size: 16.0,
+					..default()
+				},
+				Node {
+					position_type: PositionType::Absolute,
+					top: Val::Px(8.0),
+					left: Val::Px(8.0),
+					..default()
+				},
+				visibility,
+			));
+		})
+		.add_systems(Update, (toggle_overlay, update_overlay).chain());
+	}
+}
+
+fn toggle_overlay(
+	keys: Res<ButtonInput<KeyCode>>,
+	mut overlay: Query<&mut Visibility, With<DiagnosticsText>>,
+) {
+	if !keys.just_pressed(DIAGNOSTICS_TOGGLE_KEY) {
+		return;
+	}
+
+	for mut visibility in &mut overlay {
+		visibility.toggle_inherited_hidden();
+	}
+}
+
+fn update_overlay(
+	diagnostics: Res<DiagnosticsStore>,
+	meshes: Res<Assets<Mesh>>,
+	visible_meshes: Query<(&Mesh3d, &ViewVisibility)>,
+	mut overlay: Query<(&mut Text, &Visibility), With<DiagnosticsText>>,
+) {
+	let Ok((mut text, visibility)) = overlay.single_mut() else {
+		return;
+	};
+	if *visibility == Visibility::Hidden {
+		return;
+	}
+
+	let fps = smoothed(&diagnostics, &FrameTimeDiagnosticsPlugin::FPS);
+	let frame_time = smoothed(&diagnostics, &FrameTimeDiagnosticsPlugin::FRAME_TIME);
+
+	let vertices: usize = visible_meshes
+		.iter()
+		.filter(|(_, view_visibility)| view_visibility.get())
+		.filter_map(|(mesh, _)| meshes.get(&mesh.0))
+		.map(Mesh::count_vertices)
+		.sum();
+
+	text.0 = format!("FPS: {fps:.0}\nFrame: {frame_time:.2} ms\nVertices: {vertices}");
+}
+
+fn smoothed(diagnostics: &DiagnosticsStore, path: &DiagnosticPath) -> f64 {
+	diagnostics
+		.get(path)
+		.and_then(|diagnostic| diagnostic.smoothed())
+		.unwrap_or_default()
+}
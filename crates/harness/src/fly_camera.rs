@@ -0,0 +1,100 @@
+//! Free-fly camera controls for interactive mode.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::prelude::*;
+
+/// Pitch limit just short of straight up/down, so the view never flips over
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+/// Adds WASD movement and mouse-look to every `Camera3d`.
+///
+/// - `W`/`A`/`S`/`D` move, `E`/`Q` move up/down, `Shift` moves faster
+/// - Hold the right mouse button and move the mouse to look around
+#[derive(Clone, Copy)]
+pub struct FlyCameraPlugin {
+	/// Movement speed in world units per second
+	pub move_speed: f32,
+	/// Radians of rotation per pixel of mouse movement
+	pub look_sensitivity: f32,
+}
+
+impl Default for FlyCameraPlugin {
+	fn default() -> Self {
+		Self {
+			move_speed: 10.0,
+			look_sensitivity: 0.003,
+		}
+	}
+}
+
+/// Runtime settings of the [`FlyCameraPlugin`]
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct FlyCameraSettings {
+	pub move_speed: f32,
+	pub look_sensitivity: f32,
+	/// Speed multiplier while `Shift` is held
+	pub boost: f32,
+}
+
+impl Plugin for FlyCameraPlugin {
+	fn build(&self, app: &mut App) {
+		app.insert_resource(FlyCameraSettings {
+			move_speed: self.move_speed,
+			look_sensitivity: self.look_sensitivity,
+			boost: 4.0,
+		})
+		.add_systems(Update, (fly_camera_look, fly_camera_move).chain());
+	}
+}
+
+fn fly_camera_look(
+	settings: Res<FlyCameraSettings>,
+	buttons: Res<ButtonInput<MouseButton>>,
+	motion: Res<AccumulatedMouseMotion>,
+	mut cameras: Query<&mut Transform, With<Camera3d>>,
+) {
+	if !buttons.pressed(MouseButton::Right) || motion.delta == Vec2::ZERO {
+		return;
+	}
+
+	let delta = motion.delta * settings.look_sensitivity;
+	for mut transform in &mut cameras {
+		let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+		let pitch = (pitch - delta.y).clamp(-MAX_PITCH, MAX_PITCH);
+		transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw - delta.x, pitch, 0.0);
+	}
+}
+
+fn fly_camera_move(
+	settings: Res<FlyCameraSettings>,
+	keys: Res<ButtonInput<KeyCode>>,
+	time: Res<Time>,
+	mut cameras: Query<&mut Transform, With<Camera3d>>,
+) {
+	let axis = |positive, negative| {
+		keys.pressed(positive) as i8 as f32 - keys.pressed(negative) as i8 as f32
+	};
+	let input = Vec3::new(
+		axis(KeyCode::KeyD, KeyCode::KeyA),
+		axis(KeyCode::KeyE, KeyCode::KeyQ),
+		axis(KeyCode::KeyW, KeyCode::KeyS),
+	);
+	if input == Vec3::ZERO {
+		return;
+	}
+
+	let boost = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+		settings.boost
+	} else {
+		1.0
+	};
+	let step = settings.move_speed * boost * time.delta_secs();
+
+	for mut transform in &mut cameras {
+		let direction =
+			transform.right() * input.x + Vec3::Y * input.y + transform.forward() * input.z;
+		transform.translation += direction.normalize_or_zero() * step;
+	}
+}
@@ -6,12 +6,16 @@ use bevy::app::ScheduleRunnerPlugin;
 use bevy::log::{Level, LogPlugin, tracing_subscriber};
 use bevy::prelude::*;
 use bevy::render::RenderPlugin;
-use bevy::render::settings::{InstanceFlags, RenderCreation, WgpuSettings};
+use bevy::render::settings::{Backends, InstanceFlags, RenderCreation, WgpuSettings};
 use bevy::window::ExitCondition;
 use bevy::winit::WinitPlugin;
+use clap::ValueEnum;
 use tracing_subscriber::fmt::format::FmtSpan;
 
 use crate::config::CliArgs;
+use crate::diagnostics::DiagnosticsOverlayPlugin;
+use crate::fly_camera::FlyCameraPlugin;
+use crate::interactive_capture::InteractiveCapturePlugin;
 
 /// Absolute path to workspace root, computed at compile time.
 pub const WORKSPACE_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
@@ -31,6 +35,50 @@ pub fn set_workspace_asset_root() {
 	}
 }
 
+/// Graphics API selectable with `--backend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphicsBackend {
+	Vulkan,
+	Dx12,
+	Metal,
+	Gl,
+}
+
+impl From<GraphicsBackend> for Backends {
+	fn from(backend: GraphicsBackend) -> Self {
+		match backend {
+			GraphicsBackend::Vulkan => Backends::VULKAN,
+			GraphicsBackend::Dx12 => Backends::DX12,
+			GraphicsBackend::Metal => Backends::METAL,
+			GraphicsBackend::Gl => Backends::GL,
+		}
+	}
+}
+
+/// wgpu settings for headless rendering, honoring `--backend`, `--adapter` and
+/// `--force-software`.
+///
+/// A requested backend that isn't available on the machine leaves no adapter to pick,
+/// and Bevy panics at startup with its "Unable to find a GPU" error. An `--adapter`
+/// name that matches no adapter (case-insensitive substring) is not fatal: Bevy falls
+/// back to the default adapter for the selected backends. `--backend` overrides the
+/// `WGPU_BACKEND` environment variable, while `WGPU_ADAPTER_NAME` overrides `--adapter`.
+///
+/// With `--force-software`, wgpu picks its fallback (CPU) adapter, such as Lavapipe on
+/// Linux with Mesa installed; startup fails if no software driver is present.
+pub fn headless_wgpu_settings() -> WgpuSettings {
+	let args = CliArgs::get();
+	let defaults = WgpuSettings::default();
+
+	WgpuSettings {
+		instance_flags: InstanceFlags::empty(),
+		backends: args.backend.map(Backends::from).or(defaults.backends),
+		adapter_name: args.adapter.as_ref().map(|name| name.to_lowercase()),
+		force_fallback_adapter: args.force_software,
+		..defaults
+	}
+}
+
 fn log_plugin(filter: &str) -> LogPlugin {
 	LogPlugin {
 		filter: filter.to_string(),
@@ -67,10 +115,7 @@ pub fn headless_plugins(log_filter: Option<&str>) -> bevy::app::PluginGroupBuild
 		})
 		.set(log_plugin(filter))
 		.set(RenderPlugin {
-			render_creation: RenderCreation::Automatic(WgpuSettings {
-				instance_flags: InstanceFlags::empty(),
-				..default()
-			}),
+			render_creation: RenderCreation::Automatic(headless_wgpu_settings()),
 			..default()
 		})
 		.set(ImagePlugin::default_nearest())
@@ -78,12 +123,22 @@ pub fn headless_plugins(log_filter: Option<&str>) -> bevy::app::PluginGroupBuild
 }
 
 /// Creates plugins for interactive windowed rendering.
+///
+/// Includes the [`DiagnosticsOverlayPlugin`], shown on startup when `--diagnostics`
+/// is passed and toggled with F3, the [`FlyCameraPlugin`] for free-fly controls, and
+/// the [`InteractiveCapturePlugin`] saving the current view on F12.
 pub fn interactive_plugins(log_filter: Option<&str>) -> bevy::app::PluginGroupBuilder {
 	let filter = log_filter.unwrap_or(
 		"wgpu=off,wgpu_hal=off,naga=off,bevy_render=off,bevy_diagnostic=off,bevy_winit=off",
 	);
 
-	DefaultPlugins.set(log_plugin(filter))
+	DefaultPlugins
+		.set(log_plugin(filter))
+		.add(DiagnosticsOverlayPlugin {
+			visible: CliArgs::get().diagnostics,
+		})
+		.add(FlyCameraPlugin::default())
+		.add(InteractiveCapturePlugin::default())
 }
 
 /// Creates a ScheduleRunnerPlugin for headless operation.
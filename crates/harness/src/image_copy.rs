@@ -1,5 +1,6 @@
 //! GPU to CPU image copy via render graph.
 
+use std::fmt;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -9,20 +10,51 @@ use bevy::render::render_graph::{
 	self, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel,
 };
 use bevy::render::render_resource::{
-	Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, MapMode, PollType,
-	TexelCopyBufferInfo, TexelCopyBufferLayout,
+	Buffer, BufferAsyncError, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d,
+	MapMode, PollType, TexelCopyBufferInfo, TexelCopyBufferLayout, TextureFormat,
 };
 use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
 use bevy::render::{Extract, Render, RenderApp, RenderSystems};
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvError, Sender};
+
+/// Failure while reading a rendered image back from the GPU
+#[derive(Debug, Clone)]
+pub enum ImageCopyError {
+	/// Mapping the readback buffer failed
+	Map(String),
+	/// Polling the device for the mapping failed
+	Poll(String),
+}
+
+impl fmt::Display for ImageCopyError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Map(err) => write!(f, "failed to map readback buffer: {err}"),
+			Self::Poll(err) => write!(f, "failed to poll render device: {err}"),
+		}
+	}
+}
+
+impl std::error::Error for ImageCopyError {}
+
+/// Raw bytes read back from a render target
+pub struct CopiedImage {
+	/// Render target the bytes were copied from
+	pub source: AssetId<Image>,
+	/// Row-padded image data
+	pub data: Vec<u8>,
+}
+
+/// Result of a single GPU readback
+pub type ImageCopyResult = Result<CopiedImage, ImageCopyError>;
 
 /// Channel receiver for image data from render world
 #[derive(Resource, Deref)]
-pub struct MainWorldReceiver(pub Receiver<Vec<u8>>);
+pub struct MainWorldReceiver(pub Receiver<ImageCopyResult>);
 
 /// Channel sender for image data to main world
 #[derive(Resource, Deref)]
-pub struct RenderWorldSender(Sender<Vec<u8>>);
+pub struct RenderWorldSender(Sender<ImageCopyResult>);
 
 /// Plugin for copying rendered images from GPU to CPU via render graph.
 pub struct ImageCopyPlugin;
@@ -61,14 +93,36 @@ pub struct ImageCopier {
 	src_image: Handle<Image>,
 }
 
+/// Bytes per row of a texture-to-buffer copy of a `width` texels wide `format` texture,
+/// padded to `COPY_BYTES_PER_ROW_ALIGNMENT`
+fn padded_bytes_per_row(format: TextureFormat, width: u32) -> usize {
+	let (block_width, _) = format.block_dimensions();
+	let block_size = format.block_copy_size(None).unwrap();
+	RenderDevice::align_copy_bytes_per_row(
+		width.div_ceil(block_width) as usize * block_size as usize,
+	)
+}
+
 impl ImageCopier {
-	pub fn new(src_image: Handle<Image>, size: Extent3d, render_device: &RenderDevice) -> Self {
-		let padded_bytes_per_row =
-			RenderDevice::align_copy_bytes_per_row((size.width) as usize) * 4;
+	/// Copier for a `size` render target of the given `format`.
+	///
+	/// The readback buffer is sized from the format's block size, so formats other than
+	/// 8-bit RGBA (such as `Rgba16Float`) get correctly padded rows.
+	///
+	/// # Panics
+	/// Panics for depth/stencil formats that have no single copyable aspect.
+	pub fn new(
+		src_image: Handle<Image>,
+		size: Extent3d,
+		format: TextureFormat,
+		render_device: &RenderDevice,
+	) -> Self {
+		let (_, block_height) = format.block_dimensions();
+		let rows = size.height.div_ceil(block_height);
 
 		let cpu_buffer = render_device.create_buffer(&BufferDescriptor {
 			label: Some("screenshot_buffer"),
-			size: padded_bytes_per_row as u64 * size.height as u64,
+			size: padded_bytes_per_row(format, size.width) as u64 * rows as u64,
 			usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
 			mapped_at_creation: false,
 		});
@@ -80,6 +134,11 @@ impl ImageCopier {
 		}
 	}
 
+	/// Render target this copier reads from
+	pub fn source(&self) -> &Handle<Image> {
+		&self.src_image
+	}
+
 	pub fn enabled(&self) -> bool {
 		self.enabled.load(Ordering::Relaxed)
 	}
@@ -97,7 +156,10 @@ fn image_copy_extract(mut commands: Commands, image_copiers: Extract<Query<&Imag
 #[derive(Debug, PartialEq, Eq, Clone, Hash, RenderLabel)]
 struct ImageCopyLabel;
 
-/// Render graph node that copies texture to buffer
+/// Render graph node that copies texture to buffer.
+///
+/// Runs after the camera driver, so MSAA has already been resolved into the
+/// single-sampled render target it reads from.
 #[derive(Default)]
 struct ImageCopyDriver;
 
@@ -126,12 +188,8 @@ impl render_graph::Node for ImageCopyDriver {
 				.render_device()
 				.create_command_encoder(&CommandEncoderDescriptor::default());
 
-			let block_dimensions = src_image.texture_format.block_dimensions();
-			let block_size = src_image.texture_format.block_copy_size(None).unwrap();
-
-			let padded_bytes_per_row = RenderDevice::align_copy_bytes_per_row(
-				(src_image.size.width as usize / block_dimensions.0 as usize) * block_size as usize,
-			);
+			let padded_bytes_per_row =
+				padded_bytes_per_row(src_image.texture_format, src_image.size.width);
 
 			encoder.copy_texture_to_buffer(
 				src_image.texture.as_image_copy(),
@@ -158,7 +216,10 @@ impl render_graph::Node for ImageCopyDriver {
 	}
 }
 
-/// Receives image data from GPU buffer and sends to main world
+/// Receives image data from GPU buffer and sends to main world.
+///
+/// Mapping and polling failures are forwarded as [`ImageCopyError`] rather than
+/// panicking, so a transient GPU error only costs the affected frame.
 fn receive_image_from_buffer(
 	image_copiers: Res<ImageCopiers>,
 	render_device: Res<RenderDevice>,
@@ -173,19 +234,92 @@ fn receive_image_from_buffer(
 
 		let (s, r) = crossbeam_channel::bounded(1);
 
-		buffer_slice.map_async(MapMode::Read, move |result| match result {
-			Ok(()) => s.send(()).expect("Failed to send map update"),
-			Err(err) => panic!("Failed to map buffer: {err}"),
+		buffer_slice.map_async(MapMode::Read, move |result| {
+			let _ = s.send(result);
 		});
 
-		render_device
-			.poll(PollType::wait_indefinitely())
-			.expect("Failed to poll device");
+		if let Err(err) = render_device.poll(PollType::wait_indefinitely()) {
+			// Cancel the pending map, or the next frame's copy into this buffer fails validation
+			image_copier.buffer.unmap();
+			let _ = sender.send(Err(ImageCopyError::Poll(err.to_string())));
+			continue;
+		}
 
-		r.recv().expect("Failed to receive map_async message");
+		let result = readback_result(image_copier.src_image.id(), r.recv(), || {
+			let data = buffer_slice.get_mapped_range().to_vec();
+			image_copier.buffer.unmap();
+			data
+		});
+
+		let _ = sender.send(result);
+	}
+}
+
+/// Converts the outcome of a readback buffer mapping into an [`ImageCopyResult`].
+///
+/// `read` copies the mapped bytes and unmaps the buffer; it only runs if the mapping
+/// succeeded, since a failed mapping leaves nothing to unmap.
+fn readback_result(
+	source: AssetId<Image>,
+	mapped: Result<Result<(), BufferAsyncError>, RecvError>,
+	read: impl FnOnce() -> Vec<u8>,
+) -> ImageCopyResult {
+	match mapped {
+		Ok(Ok(())) => Ok(CopiedImage {
+			source,
+			data: read(),
+		}),
+		Ok(Err(err)) => Err(ImageCopyError::Map(err.to_string())),
+		Err(_) => Err(ImageCopyError::Map("map callback was dropped".to_string())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rgba16_float_rows_use_eight_bytes_per_texel() {
+		// 100 * 8 = 800 bytes, padded to 1024
+		assert_eq!(padded_bytes_per_row(TextureFormat::Rgba16Float, 100), 1024);
+		// 1366 * 8 = 10928 bytes, padded to 11008
+		assert_eq!(
+			padded_bytes_per_row(TextureFormat::Rgba16Float, 1366),
+			11008
+		);
+		assert_eq!(padded_bytes_per_row(TextureFormat::Rgba16Float, 64), 512);
+	}
+
+	#[test]
+	fn rgba8_rows_use_four_bytes_per_texel() {
+		assert_eq!(
+			padded_bytes_per_row(TextureFormat::Rgba8UnormSrgb, 100),
+			512
+		);
+		assert_eq!(padded_bytes_per_row(TextureFormat::Rgba8UnormSrgb, 64), 256);
+	}
+
+	#[test]
+	fn failed_map_is_reported_without_reading() {
+		let result = readback_result(AssetId::default(), Ok(Err(BufferAsyncError)), || {
+			panic!("read a buffer that failed to map")
+		});
+		assert!(matches!(result, Err(ImageCopyError::Map(_))));
+	}
 
-		let _ = sender.send(buffer_slice.get_mapped_range().to_vec());
+	#[test]
+	fn dropped_map_callback_is_reported() {
+		let result = readback_result(AssetId::default(), Err(RecvError), || {
+			panic!("read a buffer whose mapping never completed")
+		});
+		assert!(matches!(result, Err(ImageCopyError::Map(_))));
+	}
 
-		image_copier.buffer.unmap();
+	#[test]
+	fn successful_map_returns_data() {
+		let source = AssetId::<Image>::default();
+		let copied = readback_result(source, Ok(Ok(())), || vec![1, 2, 3]).unwrap();
+		assert_eq!(copied.source, source);
+		assert_eq!(copied.data, [1, 2, 3]);
 	}
 }
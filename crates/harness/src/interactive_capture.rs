@@ -0,0 +1,86 @@
+//! Keypress-triggered screenshots in interactive mode.
+
+use std::path::PathBuf;
+
+use bevy::camera::RenderTarget;
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+
+use crate::config::CliArgs;
+use crate::state::unix_millis;
+
+/// Saves the current view of the first `Camera3d` when a key is pressed.
+///
+/// Frames go to `{output_dir}/{example}/screenshots/{session}/{timestamp}.png`, the
+/// same layout headless sessions use, with the example name taken from the executable.
+///
+/// This uses Bevy's screenshot readback rather than the headless `ImageCopier` path,
+/// which needs an image render target allocated up front. Readback works for both
+/// window and image render targets.
+pub struct InteractiveCapturePlugin {
+	/// Key that triggers a capture
+	pub key: KeyCode,
+}
+
+impl Default for InteractiveCapturePlugin {
+	fn default() -> Self {
+		Self { key: KeyCode::F12 }
+	}
+}
+
+#[derive(Resource)]
+struct InteractiveCapture {
+	key: KeyCode,
+	session_dir: PathBuf,
+}
+
+impl Plugin for InteractiveCapturePlugin {
+	fn build(&self, app: &mut App) {
+		let example_name = std::env::current_exe()
+			.ok()
+			.and_then(|exe| {
+				exe.file_stem()
+					.map(|stem| stem.to_string_lossy().into_owned())
+			})
+			.unwrap_or_else(|| "interactive".to_string());
+
+		let session_dir = CliArgs::get()
+			.output_dir
+			.join(example_name)
+			.join("screenshots")
+			.join(unix_millis().to_string());
+
+		app.insert_resource(InteractiveCapture {
+			key: self.key,
+			session_dir,
+		})
+		.add_systems(Update, capture_on_keypress);
+	}
+}
+
+fn capture_on_keypress(
+	mut commands: Commands,
+	capture: Res<InteractiveCapture>,
+	keys: Res<ButtonInput<KeyCode>>,
+	cameras: Query<&RenderTarget, With<Camera3d>>,
+) {
+	if !keys.just_pressed(capture.key) {
+		return;
+	}
+
+	let Some(target) = cameras.iter().next() else {
+		warn!("No camera to capture");
+		return;
+	};
+
+	if let Err(e) = std::fs::create_dir_all(&capture.session_dir) {
+		error!(%e, path = ?capture.session_dir, "Failed to create screenshot directory");
+		return;
+	}
+
+	let path = capture.session_dir.join(format!("{}.png", unix_millis()));
+	info!(path = %path.display(), "saving");
+	commands
+		.spawn(Screenshot(target.clone()))
+		.observe(save_to_disk(path));
+}
@@ -5,24 +5,53 @@
 
 #![allow(dead_code)]
 
+mod camera_poses;
+mod capture_once;
 mod cleanup;
 mod config;
+mod contact_sheet;
+mod diagnostics;
+mod fly_camera;
 mod headless;
 mod image_copy;
+mod interactive_capture;
+mod loading;
+mod manifest;
 mod plugin;
 mod presets;
+mod processing;
 mod state;
 mod systems;
 
-pub use cleanup::cleanup_old_sessions;
-pub use config::{CliArgs, ScreenshotConfig};
+pub use camera_poses::{CAMERA_POSES_FILE, CameraPose, CameraPoseFile};
+pub use capture_once::capture_once;
+pub use cleanup::{
+	RetainPolicy, cleanup_old_sessions, cleanup_sessions, cleanup_sessions_older_than,
+	cleanup_sessions_to_budget, plan_cleanup,
+};
+pub use config::{
+	CaptureMode, CliArgs, ColorGrade, ConfigError, DEFAULT_AGENT_INSTRUCTIONS, LightingConfig,
+	OutputFormat, ScreenshotConfig,
+};
+pub use contact_sheet::CONTACT_SHEET_FILE;
+pub use diagnostics::{DIAGNOSTICS_TOGGLE_KEY, DiagnosticsOverlayPlugin};
+pub use fly_camera::{FlyCameraPlugin, FlyCameraSettings};
 pub use headless::{
-	WORKSPACE_ROOT, headless_plugins, headless_runner, interactive_plugins, is_interactive,
-	set_workspace_asset_root,
+	GraphicsBackend, WORKSPACE_ROOT, headless_plugins, headless_runner, headless_wgpu_settings,
+	interactive_plugins, is_interactive, set_workspace_asset_root,
+};
+pub use interactive_capture::InteractiveCapturePlugin;
+pub use loading::TrackLoading;
+pub use manifest::{
+	CaptureRecord, MANIFEST_FILE, PresetTiming, SessionManifest, SessionTiming, WAVE_MAP_PRESET,
+	WaveMapMapping,
 };
 pub use plugin::{HarnessCameraReady, ScreenshotHarnessPlugin};
 pub use presets::{
-	CameraPreset, DETAIL_PRESETS, MIN_CAMERA_HEIGHT, SIMPLE_PRESETS, STANDARD_PRESETS,
+	CameraPreset, DETAIL_PRESETS, MIN_CAMERA_HEIGHT, Placement, PolarPreset, PresetLoadError,
+	SIMPLE_PRESETS, STANDARD_PRESETS, load_presets,
+};
+pub use state::{
+	CAPTURE_FAILED_EXIT_CODE, CaptureProgress, ScreenshotPhase, ScreenshotResults, ScreenshotState,
 };
-pub use state::{ScreenshotPhase, ScreenshotState};
 pub use systems::{MainCamera, setup_camera};
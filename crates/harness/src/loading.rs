@@ -0,0 +1,45 @@
+//! Asset load tracking used to gate the first capture.
+
+use bevy::asset::{LoadState, RecursiveDependencyLoadState, UntypedHandle};
+use bevy::prelude::*;
+
+use crate::config::ScreenshotConfig;
+use crate::state::ScreenshotState;
+
+/// Registers an asset the harness should wait for before capturing.
+///
+/// Only consulted when [`ScreenshotConfig::wait_for_assets`] is set.
+#[derive(Component, Clone, Debug)]
+pub struct TrackLoading(pub UntypedHandle);
+
+impl<A: Asset> From<Handle<A>> for TrackLoading {
+	fn from(handle: Handle<A>) -> Self {
+		Self(handle.untyped())
+	}
+}
+
+/// Updates [`ScreenshotState::assets_ready`] from the load state of all tracked assets.
+///
+/// Failed loads count as settled so a missing file doesn't stall the sequence.
+pub(crate) fn update_asset_readiness(
+	config: Res<ScreenshotConfig>,
+	asset_server: Res<AssetServer>,
+	tracked: Query<&TrackLoading>,
+	mut state: ResMut<ScreenshotState>,
+) {
+	if !config.wait_for_assets {
+		return;
+	}
+
+	state.assets_ready = tracked.iter().all(|TrackLoading(handle)| {
+		let loaded = matches!(
+			asset_server.get_load_state(handle.id()),
+			Some(LoadState::Loaded | LoadState::Failed(_))
+		);
+		let dependencies_loaded = matches!(
+			asset_server.get_recursive_dependency_load_state(handle.id()),
+			Some(RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_))
+		);
+		loaded && dependencies_loaded
+	});
+}
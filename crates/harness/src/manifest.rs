@@ -0,0 +1,110 @@
+//! Session manifest describing captured screenshots.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+/// File name of the manifest written into each session directory
+pub const MANIFEST_FILE: &str = "metadata.json";
+
+/// Preset name (and file stem) of the top-down wave map capture
+pub const WAVE_MAP_PRESET: &str = "wave_map";
+
+/// Metadata for a single captured screenshot
+#[derive(Serialize, Debug, Clone)]
+pub struct CaptureRecord {
+	/// Preset name the capture was taken with
+	pub preset: String,
+	/// Saved image path
+	pub path: String,
+	/// Saved image width in pixels
+	pub width: u32,
+	/// Saved image height in pixels
+	pub height: u32,
+	/// Downscaled copies written for the configured extra resolutions
+	pub extra_paths: Vec<String>,
+	/// World-space camera position at capture time
+	pub camera_position: [f32; 3],
+	/// World-space point the camera was looking at
+	pub look_target: [f32; 3],
+	/// Capture time in milliseconds since the Unix epoch
+	pub timestamp_ms: u64,
+	/// Whether this is the wireframe overlay pass of the preset
+	pub wireframe: bool,
+}
+
+/// Self-describing metadata for a screenshot session
+#[derive(Serialize, Debug, Clone)]
+pub struct SessionManifest {
+	pub example_name: String,
+	pub session: String,
+	pub width: u32,
+	pub height: u32,
+	/// Number of active waves in the scene, if the example reported one
+	pub wave_count: Option<u32>,
+	pub captures: Vec<CaptureRecord>,
+	/// Pixel-to-world mapping of the wave map capture, if one was taken
+	pub wave_map: Option<WaveMapMapping>,
+	/// Wall-clock timing of the session
+	pub timing: SessionTiming,
+}
+
+/// Wall-clock timing of a screenshot session
+#[derive(Serialize, Debug, Clone)]
+pub struct SessionTiming {
+	/// Milliseconds from setup until the sequence finished
+	pub total_ms: f64,
+	/// Frames the sequence ran before finishing
+	pub frames: u32,
+	pub presets: Vec<PresetTiming>,
+}
+
+/// Frames and wall-clock time spent pre-rolling or settling and capturing one preset
+#[derive(Serialize, Debug, Clone)]
+pub struct PresetTiming {
+	pub preset: String,
+	pub frames: u32,
+	pub duration_ms: f64,
+}
+
+/// Linear mapping between wave map pixels and world XZ coordinates
+#[derive(Serialize, Debug, Clone)]
+pub struct WaveMapMapping {
+	pub convention: &'static str,
+	/// World (X, Z) at the top-left corner of the image
+	pub world_min: [f32; 2],
+	/// World (X, Z) at the bottom-right corner of the image
+	pub world_max: [f32; 2],
+	/// World units covered by one pixel along X and Z
+	pub units_per_pixel: [f32; 2],
+}
+
+impl WaveMapMapping {
+	/// Mapping for a capture of `area` at `width`×`height` output pixels
+	pub fn new(area: bevy::math::Rect, width: u32, height: u32) -> Self {
+		Self {
+			convention: "Pixel (px, py) covers world X = world_min[0] + (px + 0.5) * \
+				units_per_pixel[0], Z = world_min[1] + (py + 0.5) * units_per_pixel[1]; \
+				image right is +X, image down is +Z",
+			world_min: area.min.to_array(),
+			world_max: area.max.to_array(),
+			units_per_pixel: [
+				area.width() / width.max(1) as f32,
+				area.height() / height.max(1) as f32,
+			],
+		}
+	}
+}
+
+impl SessionManifest {
+	/// Writes the manifest as pretty-printed JSON.
+	///
+	/// The data is written to a temporary file first and then renamed into place,
+	/// so readers never observe a partially written manifest.
+	pub fn write_atomic(&self, path: &Path) -> std::io::Result<()> {
+		let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+		let tmp_path = path.with_extension("json.tmp");
+		std::fs::write(&tmp_path, json)?;
+		std::fs::rename(&tmp_path, path)
+	}
+}
@@ -1,40 +1,117 @@
 //! Screenshot harness plugin.
 
+use bevy::pbr::wireframe::WireframePlugin;
 use bevy::prelude::*;
+use clap::CommandFactory;
+use clap::error::ErrorKind;
+use crossbeam_channel::Sender;
 
-use crate::config::ScreenshotConfig;
+use crate::config::{CaptureMode, CliArgs, ScreenshotConfig};
 use crate::image_copy::ImageCopyPlugin;
-use crate::state::ScreenshotState;
-use crate::systems::{prepare_screenshot_dir, screenshot_sequence, setup_camera};
+use crate::loading::update_asset_readiness;
+use crate::manifest::WAVE_MAP_PRESET;
+use crate::presets::CameraPreset;
+use crate::state::{
+	CaptureProgress, ResultSender, ScreenshotPhase, ScreenshotResults, ScreenshotState,
+};
+use crate::systems::{
+	finish_session, frame_watchdog, prepare_screenshot_dir, screenshot_sequence, setup_camera,
+	setup_lighting,
+};
 
 /// Marker resource indicating the harness camera setup is complete.
 /// Use with `run_if(resource_exists::<HarnessCameraReady>)` to order systems after camera setup.
 #[derive(Resource, Default)]
 pub struct HarnessCameraReady;
 
+/// Camera height of the top-down wave map capture
+const WAVE_MAP_HEIGHT: f32 = 100.0;
+
 /// Plugin that provides the screenshot harness for examples.
 pub struct ScreenshotHarnessPlugin {
 	config: ScreenshotConfig,
+	result_sender: Option<Sender<ScreenshotResults>>,
 }
 
 impl ScreenshotHarnessPlugin {
 	pub fn new(example_name: &str) -> Self {
+		Self::with_config(ScreenshotConfig::from_cli(example_name))
+	}
+
+	pub fn with_config(config: ScreenshotConfig) -> Self {
 		Self {
-			config: ScreenshotConfig::from_cli(example_name),
+			config,
+			result_sender: None,
 		}
 	}
 
-	pub fn with_config(config: ScreenshotConfig) -> Self {
-		Self { config }
+	/// Sends the session's [`ScreenshotResults`] over `sender` once capture completes.
+	///
+	/// The send happens a single time when the sequence enters [`ScreenshotPhase::Done`],
+	/// after the session manifest is written and before `AppExit` is emitted, so the
+	/// results are available to the caller once `App::run()` returns.
+	pub fn with_result_sender(mut self, sender: Sender<ScreenshotResults>) -> Self {
+		self.result_sender = Some(sender);
+		self
 	}
 }
 
 impl Plugin for ScreenshotHarnessPlugin {
 	fn build(&self, app: &mut App) {
-		app.insert_resource(self.config.clone())
+		if let Some(sender) = &self.result_sender {
+			app.insert_resource(ResultSender(sender.clone()));
+		}
+
+		let mut config = self.config.clone();
+		config.apply_preset_filter();
+		if let Some(area) = config.wave_map {
+			config.presets.push(CameraPreset::top_down(
+				WAVE_MAP_PRESET,
+				area,
+				WAVE_MAP_HEIGHT,
+			));
+		}
+
+		if let Err(err) = config.validate() {
+			CliArgs::command()
+				.error(ErrorKind::InvalidValue, err)
+				.exit()
+		}
+
+		if config.capture_wireframe && !app.is_plugin_added::<WireframePlugin>() {
+			app.add_plugins(WireframePlugin::default());
+		}
+
+		let total = match config.capture_mode {
+			CaptureMode::Sequential if !config.multi_shot => 1,
+			_ => config.presets.len(),
+		};
+		app.insert_resource(CaptureProgress {
+			completed: 0,
+			total: total.max(1),
+		});
+
+		app.insert_resource(config)
 			.init_resource::<ScreenshotState>()
 			.add_plugins(ImageCopyPlugin)
-			.add_systems(Startup, (setup_camera, prepare_screenshot_dir))
-			.add_systems(PostUpdate, screenshot_sequence);
+			.add_systems(
+				Startup,
+				(setup_camera, setup_lighting, prepare_screenshot_dir),
+			)
+			.add_systems(
+				PostUpdate,
+				(
+					update_asset_readiness,
+					frame_watchdog,
+					screenshot_sequence,
+					finish_session
+						.run_if(sequence_done.and(not(resource_exists::<ScreenshotResults>))),
+				)
+					.chain(),
+			);
 	}
 }
+
+fn sequence_done(state: Res<ScreenshotState>) -> bool {
+	state.phase == ScreenshotPhase::Done
+}
@@ -1,100 +1,447 @@
 //! Camera presets for screenshot capture.
 
+use std::borrow::Cow;
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use bevy::camera::ScalingMode;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Minimum camera height
 pub const MIN_CAMERA_HEIGHT: f32 = 2.0;
 
+/// Where a preset places the camera and what it looks at
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Placement {
+	/// Orbit around the world origin at `radius`/`angle` in the XZ plane and `height`
+	/// above it, looking at `look_offset`. Height is clamped to [`MIN_CAMERA_HEIGHT`].
+	Polar {
+		radius: f32,
+		height: f32,
+		angle: f32,
+		#[serde(default)]
+		look_offset: Vec3,
+	},
+	/// Exact eye position and look target in world space
+	Explicit { eye: Vec3, target: Vec3 },
+	/// Orthographic camera at `height` looking straight down, framing the world XZ
+	/// rectangle `area` (`x` is world X, `y` is world Z) exactly.
+	///
+	/// Image right is +X and image down is +Z, so pixels map linearly to world XZ.
+	TopDown { area: Rect, height: f32 },
+}
+
 /// A camera position preset for screenshots
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CameraPreset {
-	pub name: &'static str,
+	/// Preset name, also used as the screenshot file name.
+	///
+	/// Names must be unique within a session: [`ScreenshotConfig::validate`] rejects
+	/// duplicates rather than renaming them, since the manifest and camera poses refer
+	/// to captures by name.
+	///
+	/// [`ScreenshotConfig::validate`]: crate::ScreenshotConfig::validate
+	pub name: Cow<'static, str>,
+	pub placement: Placement,
+	/// Vertical field of view in degrees; `None` uses Bevy's default (45°)
+	#[serde(default)]
+	pub fov_degrees: Option<f32>,
+	/// Render with an orthographic projection.
+	///
+	/// The view height is chosen so the look target is framed as the perspective
+	/// camera would frame it at the same distance and field of view.
+	#[serde(default)]
+	pub orthographic: bool,
+	/// Explicit pitch in radians (negative looks down).
+	///
+	/// When set, the camera still faces the look target horizontally but its tilt is
+	/// fixed, so the horizon sits at the same screen height regardless of distance.
+	#[serde(default)]
+	pub pitch: Option<f32>,
+	/// Frames to settle before capturing this preset, overriding the config's
+	/// `settle_frames`
+	#[serde(default)]
+	pub settle_frames: Option<u32>,
+}
+
+impl CameraPreset {
+	/// Preset orbiting the origin, looking at the origin
+	pub const fn polar(name: &'static str, radius: f32, height: f32, angle: f32) -> Self {
+		Self {
+			name: Cow::Borrowed(name),
+			placement: Placement::Polar {
+				radius,
+				height,
+				angle,
+				look_offset: Vec3::ZERO,
+			},
+			fov_degrees: None,
+			orthographic: false,
+			pitch: None,
+			settle_frames: None,
+		}
+	}
+
+	/// Preset with an exact eye position and look target
+	pub const fn explicit(name: &'static str, eye: Vec3, target: Vec3) -> Self {
+		Self {
+			name: Cow::Borrowed(name),
+			placement: Placement::Explicit { eye, target },
+			fov_degrees: None,
+			orthographic: false,
+			pitch: None,
+			settle_frames: None,
+		}
+	}
+
+	/// Top-down orthographic preset covering the world XZ rectangle `area`
+	pub const fn top_down(name: &'static str, area: Rect, height: f32) -> Self {
+		Self {
+			name: Cow::Borrowed(name),
+			placement: Placement::TopDown { area, height },
+			fov_degrees: None,
+			orthographic: true,
+			pitch: None,
+			settle_frames: None,
+		}
+	}
+
+	/// Sets the look target of a polar preset; explicit presets are unchanged
+	pub const fn with_look_offset(mut self, offset: Vec3) -> Self {
+		if let Placement::Polar { look_offset, .. } = &mut self.placement {
+			*look_offset = offset;
+		}
+		self
+	}
+
+	/// Sets the vertical field of view in degrees
+	pub const fn with_fov(mut self, degrees: f32) -> Self {
+		self.fov_degrees = Some(degrees);
+		self
+	}
+
+	/// Uses an orthographic projection instead of perspective
+	pub const fn with_orthographic(mut self) -> Self {
+		self.orthographic = true;
+		self
+	}
+
+	/// Fixes the camera pitch in radians instead of deriving it from the look target
+	pub const fn with_pitch(mut self, pitch: f32) -> Self {
+		self.pitch = Some(pitch);
+		self
+	}
+
+	/// Overrides the number of frames to settle before this preset is captured
+	pub const fn with_settle_frames(mut self, frames: u32) -> Self {
+		self.settle_frames = Some(frames);
+		self
+	}
+
+	/// World-space camera position
+	pub fn to_position(&self) -> Vec3 {
+		match self.placement {
+			Placement::Polar {
+				radius,
+				height,
+				angle,
+				..
+			} => Vec3::new(
+				radius * angle.sin(),
+				height.max(MIN_CAMERA_HEIGHT),
+				radius * angle.cos(),
+			),
+			Placement::Explicit { eye, .. } => eye,
+			Placement::TopDown { area, height } => {
+				let center = area.center();
+				Vec3::new(center.x, height, center.y)
+			}
+		}
+	}
+
+	/// World-space point the camera looks at
+	pub fn look_target(&self) -> Vec3 {
+		match self.placement {
+			Placement::Polar { look_offset, .. } => look_offset,
+			Placement::Explicit { target, .. } => target,
+			Placement::TopDown { area, .. } => {
+				let center = area.center();
+				Vec3::new(center.x, 0.0, center.y)
+			}
+		}
+	}
+
+	/// Camera transform for this preset
+	pub fn transform(&self) -> Transform {
+		let eye = self.to_position();
+		let transform = Transform::from_translation(eye);
+
+		if let Placement::TopDown { .. } = self.placement {
+			// Pitching -90° about X keeps +X to the right and turns the camera's up to -Z
+			return transform.with_rotation(Quat::from_rotation_x(-FRAC_PI_2));
+		}
+
+		match self.pitch {
+			Some(pitch) => {
+				let direction = self.look_target() - eye;
+				let yaw = (-direction.x).atan2(-direction.z);
+				transform.with_rotation(Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0))
+			}
+			None => transform.looking_at(self.look_target(), Vec3::Y),
+		}
+	}
+
+	/// Camera projection for this preset.
+	///
+	/// Scene systems that build geometry from the camera projection (such as a
+	/// projected-grid ocean) need to regenerate on `Changed<Projection>`, since the
+	/// harness swaps projections when it moves between presets.
+	pub fn projection(&self) -> Projection {
+		let default = PerspectiveProjection::default();
+		let fov = self.fov_degrees.map_or(default.fov, f32::to_radians);
+
+		if let Placement::TopDown { area, height } = self.placement {
+			let default = OrthographicProjection::default_3d();
+			return Projection::Orthographic(OrthographicProjection {
+				scaling_mode: ScalingMode::Fixed {
+					width: area.width(),
+					height: area.height(),
+				},
+				far: default.far.max(height * 2.0),
+				..default
+			});
+		}
+
+		if self.orthographic {
+			let distance = self.to_position().distance(self.look_target());
+			Projection::Orthographic(OrthographicProjection {
+				scaling_mode: ScalingMode::FixedVertical {
+					viewport_height: 2.0 * distance * (fov / 2.0).tan(),
+				},
+				..OrthographicProjection::default_3d()
+			})
+		} else {
+			Projection::Perspective(PerspectiveProjection { fov, ..default })
+		}
+	}
+
+	/// Creates `count` presets evenly spaced in angle around `look`, named `orbit_0`, `orbit_1`, ...
+	///
+	/// Each camera sits `radius` from `look` in the XZ plane and `height` above it,
+	/// facing `look`, so the ring is a turntable around the target.
+	pub fn orbit_ring(count: usize, radius: f32, height: f32, look: Vec3) -> Vec<CameraPreset> {
+		(0..count)
+			.map(|i| {
+				let angle = i as f32 * TAU / count as f32;
+				let eye = look + Vec3::new(radius * angle.sin(), height, radius * angle.cos());
+				CameraPreset {
+					name: Cow::Owned(format!("orbit_{i}")),
+					..CameraPreset::explicit("", eye, look)
+				}
+			})
+			.collect()
+	}
+}
+
+/// The polar fields `CameraPreset` had before [`Placement`] was introduced.
+///
+/// Kept so existing struct literals keep working through a conversion:
+/// `PolarPreset { name, radius, height, angle, look_offset }.into()`.
+#[derive(Clone, Debug)]
+pub struct PolarPreset {
+	pub name: Cow<'static, str>,
 	pub radius: f32,
 	pub height: f32,
 	pub angle: f32,
 	pub look_offset: Vec3,
 }
 
-impl CameraPreset {
-	pub fn to_position(self) -> Vec3 {
-		let x = self.radius * self.angle.sin();
-		let z = self.radius * self.angle.cos();
-		let y = self.height.max(MIN_CAMERA_HEIGHT);
-		Vec3::new(x, y, z)
+impl From<PolarPreset> for CameraPreset {
+	fn from(preset: PolarPreset) -> Self {
+		Self {
+			name: preset.name,
+			..CameraPreset::polar("", preset.radius, preset.height, preset.angle)
+				.with_look_offset(preset.look_offset)
+		}
+	}
+}
+
+/// Error loading camera presets from a file
+#[derive(Debug)]
+pub enum PresetLoadError {
+	Io(std::io::Error),
+	Ron(ron::error::SpannedError),
+	Json(serde_json::Error),
+	/// The file extension is neither `.ron` nor `.json`
+	UnsupportedFormat(PathBuf),
+}
+
+impl fmt::Display for PresetLoadError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(err) => write!(f, "failed to read presets file: {err}"),
+			Self::Ron(err) => write!(f, "invalid RON presets file: {err}"),
+			Self::Json(err) => write!(f, "invalid JSON presets file: {err}"),
+			Self::UnsupportedFormat(path) => write!(
+				f,
+				"unsupported presets file {}: expected a .ron or .json extension",
+				path.display()
+			),
+		}
+	}
+}
+
+impl std::error::Error for PresetLoadError {}
+
+/// Loads a list of camera presets from a `.ron` or `.json` file.
+///
+/// The file holds a sequence of uniquely named presets; `fov_degrees`, `orthographic`,
+/// `pitch`, `settle_frames` and a polar `look_offset` may be omitted. For example, in RON:
+///
+/// ```ron
+/// [
+///     (name: "wide", placement: Polar(radius: 120.0, height: 35.0, angle: 0.0)),
+///     (name: "bow", placement: Explicit(eye: (0.0, 5.0, 30.0), target: (0.0, 2.0, 0.0))),
+/// ]
+/// ```
+pub fn load_presets(path: &Path) -> Result<Vec<CameraPreset>, PresetLoadError> {
+	let contents = std::fs::read_to_string(path).map_err(PresetLoadError::Io)?;
+
+	match path.extension().and_then(|ext| ext.to_str()) {
+		Some("ron") => ron::from_str(&contents).map_err(PresetLoadError::Ron),
+		Some("json") => serde_json::from_str(&contents).map_err(PresetLoadError::Json),
+		_ => Err(PresetLoadError::UnsupportedFormat(path.to_path_buf())),
 	}
 }
 
 /// Standard camera presets for visualization
 pub const STANDARD_PRESETS: &[CameraPreset] = &[
-	CameraPreset {
-		name: "wide",
-		radius: 120.0,
-		height: 35.0,
-		angle: 0.0,
-		look_offset: Vec3::ZERO,
-	},
-	CameraPreset {
-		name: "close",
-		radius: 40.0,
-		height: 15.0,
-		angle: 2.5,
-		look_offset: Vec3::ZERO,
-	},
-	CameraPreset {
-		name: "dramatic",
-		radius: 70.0,
-		height: 20.0,
-		angle: 5.5,
-		look_offset: Vec3::new(10.0, 0.0, 10.0),
-	},
+	CameraPreset::polar("wide", 120.0, 35.0, 0.0),
+	CameraPreset::polar("close", 40.0, 15.0, 2.5),
+	CameraPreset::polar("dramatic", 70.0, 20.0, 5.5).with_look_offset(Vec3::new(10.0, 0.0, 10.0)),
 ];
 
 /// Close-up presets for detail inspection
 pub const DETAIL_PRESETS: &[CameraPreset] = &[
-	CameraPreset {
-		name: "detail_top",
-		radius: 20.0,
-		height: 30.0,
-		angle: 0.0,
-		look_offset: Vec3::ZERO,
-	},
-	CameraPreset {
-		name: "detail_angle",
-		radius: 15.0,
-		height: 8.0,
-		angle: 0.8,
-		look_offset: Vec3::ZERO,
-	},
-	CameraPreset {
-		name: "detail_low",
-		radius: 12.0,
-		height: 3.0,
-		angle: 1.2,
-		look_offset: Vec3::ZERO,
-	},
+	CameraPreset::polar("detail_top", 20.0, 30.0, 0.0),
+	CameraPreset::polar("detail_angle", 15.0, 8.0, 0.8),
+	CameraPreset::polar("detail_low", 12.0, 3.0, 1.2),
 ];
 
 /// Simple presets for basic examples (cube, etc.)
 pub const SIMPLE_PRESETS: &[CameraPreset] = &[
-	CameraPreset {
-		name: "front",
-		radius: 5.0,
-		height: 3.0,
-		angle: 0.5,
-		look_offset: Vec3::ZERO,
-	},
-	CameraPreset {
-		name: "angle",
-		radius: 6.0,
-		height: 4.0,
-		angle: 2.0,
-		look_offset: Vec3::ZERO,
-	},
-	CameraPreset {
-		name: "top",
-		radius: 4.0,
-		height: 6.0,
-		angle: 0.0,
-		look_offset: Vec3::ZERO,
-	},
+	CameraPreset::polar("front", 5.0, 3.0, 0.5),
+	CameraPreset::polar("angle", 6.0, 4.0, 2.0),
+	CameraPreset::polar("top", 4.0, 6.0, 0.0),
 ];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn explicit_placement_yields_exact_transform() {
+		let eye = Vec3::new(3.0, 7.0, -12.0);
+		let target = Vec3::new(-1.0, 0.5, 4.0);
+		let transform = CameraPreset::explicit("exact", eye, target).transform();
+
+		assert_eq!(transform.translation, eye);
+		let expected = (target - eye).normalize();
+		assert!(transform.forward().dot(expected) > 1.0 - 1e-6);
+	}
+
+	#[test]
+	fn polar_fields_convert_to_polar_placement() {
+		let preset: CameraPreset = PolarPreset {
+			name: Cow::Borrowed("legacy"),
+			radius: 40.0,
+			height: 15.0,
+			angle: 2.5,
+			look_offset: Vec3::X,
+		}
+		.into();
+
+		let expected = CameraPreset::polar("legacy", 40.0, 15.0, 2.5).with_look_offset(Vec3::X);
+		assert_eq!(preset.name, "legacy");
+		assert_eq!(preset.placement, expected.placement);
+		assert_eq!(preset.transform(), expected.transform());
+	}
+
+	#[test]
+	fn presets_round_trip_through_ron_and_json() {
+		let presets = vec![
+			CameraPreset::polar("wide", 120.0, 35.0, 0.0).with_look_offset(Vec3::Y),
+			CameraPreset::explicit("bow", Vec3::new(0.0, 5.0, 30.0), Vec3::new(0.0, 2.0, 0.0))
+				.with_fov(60.0)
+				.with_settle_frames(90),
+		];
+		let dir = tempfile::tempdir().unwrap();
+
+		let ron_path = dir.path().join("presets.ron");
+		std::fs::write(&ron_path, ron::to_string(&presets).unwrap()).unwrap();
+		let json_path = dir.path().join("presets.json");
+		std::fs::write(&json_path, serde_json::to_string(&presets).unwrap()).unwrap();
+
+		for path in [ron_path, json_path] {
+			let loaded = load_presets(&path).unwrap();
+			assert_eq!(loaded.len(), 2);
+			for (loaded, original) in loaded.iter().zip(&presets) {
+				assert_eq!(loaded.name, original.name);
+				assert_eq!(loaded.placement, original.placement);
+				assert_eq!(loaded.fov_degrees, original.fov_degrees);
+				assert_eq!(loaded.settle_frames, original.settle_frames);
+			}
+		}
+	}
+
+	#[test]
+	fn omitted_optional_fields_use_defaults() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("presets.ron");
+		std::fs::write(
+			&path,
+			r#"[(name: "wide", placement: Polar(radius: 120.0, height: 35.0, angle: 0.0))]"#,
+		)
+		.unwrap();
+
+		let loaded = load_presets(&path).unwrap();
+		assert_eq!(
+			loaded[0].placement,
+			CameraPreset::polar("wide", 120.0, 35.0, 0.0).placement
+		);
+		assert_eq!(loaded[0].fov_degrees, None);
+		assert!(!loaded[0].orthographic);
+	}
+
+	#[test]
+	fn unknown_extension_is_rejected() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("presets.toml");
+		std::fs::write(&path, "").unwrap();
+
+		assert!(matches!(
+			load_presets(&path),
+			Err(PresetLoadError::UnsupportedFormat(_))
+		));
+	}
+
+	#[test]
+	fn orbit_ring_circles_the_look_target() {
+		let look = Vec3::new(10.0, 2.0, -5.0);
+		let ring = CameraPreset::orbit_ring(4, 20.0, 8.0, look);
+
+		assert_eq!(ring.len(), 4);
+		for (i, preset) in ring.iter().enumerate() {
+			assert_eq!(preset.name, format!("orbit_{i}"));
+			assert_eq!(preset.look_target(), look);
+
+			let offset = preset.to_position() - look;
+			assert!((offset.y - 8.0).abs() < 1e-4);
+			assert!((offset.xz().length() - 20.0).abs() < 1e-4);
+			let angle = offset.x.atan2(offset.z).rem_euclid(TAU);
+			assert!((angle - i as f32 * TAU / 4.0).abs() < 1e-4);
+		}
+	}
+}
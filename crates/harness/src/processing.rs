@@ -0,0 +1,237 @@
+//! CPU-side processing applied to captured images before saving.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use bevy::color::ColorToComponents;
+use bevy::math::{URect, UVec2, Vec3};
+use bevy::render::renderer::RenderDevice;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{FilterType, PngEncoder};
+use image::{DynamicImage, ImageResult, RgbaImage};
+
+use crate::config::{ColorGrade, OutputFormat};
+
+/// Strips the row padding of a GPU readback buffer.
+///
+/// Texture-to-buffer copies pad each row to `COPY_BYTES_PER_ROW_ALIGNMENT`; this
+/// returns the `height` rows of `width * bytes_per_pixel` bytes tightly packed.
+/// A truncated buffer yields only the rows it contains.
+pub fn unpad_rows(data: &[u8], width: usize, height: usize, bytes_per_pixel: usize) -> Vec<u8> {
+	let row_bytes = width * bytes_per_pixel;
+	let aligned_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+
+	if row_bytes == aligned_row_bytes {
+		return data[..data.len().min(row_bytes * height)].to_vec();
+	}
+
+	data.chunks(aligned_row_bytes)
+		.take(height)
+		.flat_map(|row| &row[..row_bytes.min(row.len())])
+		.copied()
+		.collect()
+}
+
+/// Mean absolute difference between two equally sized byte buffers, or `None` if
+/// their lengths differ.
+pub fn mean_abs_difference(a: &[u8], b: &[u8]) -> Option<f32> {
+	if a.len() != b.len() {
+		return None;
+	}
+	if a.is_empty() {
+		return Some(0.0);
+	}
+
+	let total: u64 = a.iter().zip(b).map(|(&x, &y)| x.abs_diff(y) as u64).sum();
+	Some(total as f32 / a.len() as f32)
+}
+
+/// Downsamples `image` by an integer `factor` using a box filter.
+///
+/// Each output pixel is the mean of a `factor`×`factor` block of input pixels.
+/// Trailing rows/columns that don't fill a whole block are dropped.
+pub fn downsample_box(image: &RgbaImage, factor: u32) -> RgbaImage {
+	if factor <= 1 {
+		return image.clone();
+	}
+
+	let width = image.width() / factor;
+	let height = image.height() / factor;
+	let block_area = factor * factor;
+
+	RgbaImage::from_fn(width, height, |x, y| {
+		let mut sum = [0u32; 4];
+		for dy in 0..factor {
+			for dx in 0..factor {
+				let pixel = image.get_pixel(x * factor + dx, y * factor + dy);
+				for (acc, channel) in sum.iter_mut().zip(pixel.0) {
+					*acc += channel as u32;
+				}
+			}
+		}
+		image::Rgba(sum.map(|acc| ((acc + block_area / 2) / block_area) as u8))
+	})
+}
+
+/// Crops `image` to `region`, clamped to the image bounds.
+///
+/// Returns `None` if the clamped region is empty.
+pub fn crop_to_region(image: &RgbaImage, region: URect) -> Option<RgbaImage> {
+	let bounds = UVec2::new(image.width(), image.height());
+	let min = region.min.min(bounds);
+	let max = region.max.min(bounds);
+
+	if max.x <= min.x || max.y <= min.y {
+		return None;
+	}
+
+	let size = max - min;
+	Some(image::imageops::crop_imm(image, min.x, min.y, size.x, size.y).to_image())
+}
+
+/// Rec. 709 luma coefficients
+const LUMA: Vec3 = Vec3::new(0.2126, 0.7152, 0.0722);
+
+/// Applies `grade` to every pixel of `image` in place, leaving alpha untouched.
+pub fn apply_color_grade(image: &mut RgbaImage, grade: &ColorGrade) {
+	if *grade == ColorGrade::default() {
+		return;
+	}
+
+	let exposure = grade.exposure.exp2();
+	let tint = grade.tint.to_srgba().to_vec3();
+
+	for pixel in image.pixels_mut() {
+		let [r, g, b, a] = pixel.0;
+		let mut color = Vec3::new(r as f32, g as f32, b as f32) / 255.0 * exposure;
+		color = (color - 0.5) * grade.contrast + 0.5;
+		let luma = Vec3::splat(color.dot(LUMA));
+		color = luma + (color - luma) * grade.saturation;
+		color *= tint;
+
+		let [r, g, b] = (color.clamp(Vec3::ZERO, Vec3::ONE) * 255.0)
+			.round()
+			.to_array()
+			.map(|channel| channel as u8);
+		pixel.0 = [r, g, b, a];
+	}
+}
+
+/// Encodes `image` to `path` in the given format.
+pub fn save_image(image: &RgbaImage, path: &Path, format: OutputFormat) -> ImageResult<()> {
+	let mut writer = BufWriter::new(File::create(path)?);
+
+	match format {
+		OutputFormat::Png(compression) => image.write_with_encoder(PngEncoder::new_with_quality(
+			&mut writer,
+			compression,
+			FilterType::default(),
+		)),
+		OutputFormat::Jpeg(quality) => DynamicImage::ImageRgba8(image.clone())
+			.to_rgb8()
+			.write_with_encoder(JpegEncoder::new_with_quality(&mut writer, quality)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unpad_rows_strips_row_padding() {
+		// 1366 * 4 = 5464 bytes per row, padded to 5632
+		let (width, height) = (1366, 3);
+		let padded_row = RenderDevice::align_copy_bytes_per_row(width * 4);
+		assert_eq!(padded_row, 5632);
+
+		let mut data = vec![0xff; padded_row * height];
+		for (y, row) in data.chunks_mut(padded_row).enumerate() {
+			row[..width * 4].fill(y as u8);
+		}
+
+		let unpadded = unpad_rows(&data, width, height, 4);
+		assert_eq!(unpadded.len(), width * 4 * height);
+		for (y, row) in unpadded.chunks(width * 4).enumerate() {
+			assert!(row.iter().all(|&byte| byte == y as u8));
+		}
+	}
+
+	#[test]
+	fn unpad_rows_keeps_aligned_rows() {
+		let data: Vec<u8> = (0..=255).cycle().take(64 * 4 * 2).collect();
+		assert_eq!(unpad_rows(&data, 64, 2, 4), data);
+	}
+
+	#[test]
+	fn unpad_rows_stops_at_end_of_truncated_buffer() {
+		let padded_row = RenderDevice::align_copy_bytes_per_row(1366 * 4);
+		let data = vec![1; padded_row + 100];
+
+		let unpadded = unpad_rows(&data, 1366, 3, 4);
+		assert_eq!(unpadded.len(), 1366 * 4 + 100);
+
+		let aligned = vec![1; 64 * 4 + 10];
+		assert_eq!(unpad_rows(&aligned, 64, 3, 4).len(), 64 * 4 + 10);
+	}
+
+	#[test]
+	fn mean_abs_difference_averages_byte_deltas() {
+		assert_eq!(
+			mean_abs_difference(&[10, 20, 30, 40], &[12, 18, 30, 44]),
+			Some(2.0)
+		);
+		assert_eq!(mean_abs_difference(&[], &[]), Some(0.0));
+		assert_eq!(mean_abs_difference(&[1, 2], &[1, 2, 3]), None);
+	}
+
+	#[test]
+	fn color_grade_exposure_and_tint() {
+		let mut image = RgbaImage::from_pixel(1, 1, image::Rgba([100, 50, 25, 200]));
+		apply_color_grade(
+			&mut image,
+			&ColorGrade {
+				exposure: 1.0,
+				tint: bevy::color::Color::srgb(1.0, 0.5, 1.0),
+				..ColorGrade::default()
+			},
+		);
+		assert_eq!(image.get_pixel(0, 0).0, [200, 50, 50, 200]);
+	}
+
+	#[test]
+	fn color_grade_desaturates_to_luma() {
+		let mut image = RgbaImage::from_pixel(1, 1, image::Rgba([255, 0, 0, 255]));
+		apply_color_grade(
+			&mut image,
+			&ColorGrade {
+				saturation: 0.0,
+				..ColorGrade::default()
+			},
+		);
+		// 0.2126 * 255 = 54.2
+		assert_eq!(image.get_pixel(0, 0).0, [54, 54, 54, 255]);
+	}
+
+	#[test]
+	fn color_grade_clamps_overexposed_pixels() {
+		let mut image = RgbaImage::from_pixel(1, 1, image::Rgba([200, 100, 0, 10]));
+		apply_color_grade(
+			&mut image,
+			&ColorGrade {
+				exposure: 2.0,
+				..ColorGrade::default()
+			},
+		);
+		assert_eq!(image.get_pixel(0, 0).0, [255, 255, 0, 10]);
+	}
+
+	#[test]
+	fn downsample_averages_each_block() {
+		let image = RgbaImage::from_fn(2, 2, |x, y| {
+			image::Rgba([(x + 2 * y) as u8 * 40, 0, 0, 255])
+		});
+		let output = downsample_box(&image, 2);
+		assert_eq!(output.get_pixel(0, 0).0, [60, 0, 0, 255]);
+	}
+}
@@ -1,15 +1,26 @@
 //! Screenshot capture state machine.
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use bevy::prelude::*;
+use crossbeam_channel::Sender;
 
-/// Number of frames to wait before capturing (allows scene to fully render)
+use crate::manifest::{CaptureRecord, PresetTiming};
+
+/// Default number of frames to wait before capturing (allows scene to fully render)
 pub const PRE_ROLL_FRAMES: u32 = 60;
 
-/// Number of frames to wait between shots for scene to settle
+/// Default number of frames to wait between shots for scene to settle
 pub const SETTLE_FRAMES: u32 = 30;
 
+/// Process exit code when the sequence finishes but some captures failed to decode or
+/// save, or fewer presets were captured than expected.
+///
+/// Exit code 1 means the `max_frames` watchdog aborted an unfinished sequence.
+pub const CAPTURE_FAILED_EXIT_CODE: u8 = 2;
+
 /// Current state of the screenshot sequence
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScreenshotPhase {
@@ -36,23 +47,85 @@ pub struct ScreenshotState {
 	pub current_preset: usize,
 	pub session_dir: String,
 	pub captured_paths: Vec<String>,
+	pub captures: Vec<CaptureRecord>,
 	pub render_target: Option<Handle<Image>>,
+	/// Whether all tracked assets have finished loading
+	pub assets_ready: bool,
+	/// Frames spent waiting for tracked assets after pre-roll
+	pub asset_wait_frames: u32,
+	/// Whether the current capture is the wireframe pass of the current preset
+	pub wireframe_pass: bool,
+	/// Frames the sequence has run without reaching [`ScreenshotPhase::Done`]
+	pub frames_elapsed: u32,
+	/// Whether the frame watchdog gave up on the sequence
+	pub aborted: bool,
+	/// When the session directory was prepared
+	pub started_at: Instant,
+	/// Wall-clock time from setup to [`ScreenshotPhase::Done`], once reached
+	pub total_duration: Option<Duration>,
+	/// When the preset currently being captured started pre-rolling or settling
+	pub preset_started_at: Instant,
+	/// Value of `frames_elapsed` when the current preset started
+	pub preset_start_frame: u32,
+	/// Frame counts and wall-clock time spent on each captured preset
+	pub preset_timings: Vec<PresetTiming>,
+	/// Captures that failed to decode or save
+	pub failed_captures: u32,
+	/// Last readback of each view while settling adaptively
+	pub previous_frames: HashMap<AssetId<Image>, Vec<u8>>,
 }
 
 impl Default for ScreenshotState {
 	fn default() -> Self {
-		let session_dir = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap_or_default()
-			.as_millis()
-			.to_string();
+		let session_dir = unix_millis().to_string();
 
 		Self {
 			phase: ScreenshotPhase::default(),
 			current_preset: 0,
 			session_dir,
 			captured_paths: Vec::new(),
+			captures: Vec::new(),
 			render_target: None,
+			assets_ready: false,
+			asset_wait_frames: 0,
+			wireframe_pass: false,
+			frames_elapsed: 0,
+			aborted: false,
+			started_at: Instant::now(),
+			total_duration: None,
+			preset_started_at: Instant::now(),
+			preset_start_frame: 0,
+			preset_timings: Vec::new(),
+			failed_captures: 0,
+			previous_frames: HashMap::new(),
 		}
 	}
 }
+
+/// Results of a completed screenshot session.
+///
+/// Inserted as a resource once the sequence reaches [`ScreenshotPhase::Done`].
+#[derive(Resource, Clone, Debug)]
+pub struct ScreenshotResults {
+	pub session_dir: PathBuf,
+	pub captured_paths: Vec<PathBuf>,
+}
+
+/// Number of presets captured so far in the current session
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CaptureProgress {
+	pub completed: usize,
+	pub total: usize,
+}
+
+/// Caller-owned channel receiving [`ScreenshotResults`] when the session completes
+#[derive(Resource, Deref)]
+pub(crate) struct ResultSender(pub Sender<ScreenshotResults>);
+
+/// Milliseconds since the Unix epoch
+pub(crate) fn unix_millis() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_millis() as u64
+}
@@ -1,22 +1,54 @@
 //! Camera setup and screenshot sequence systems.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bevy::core_pipeline::tonemapping::DebandDither;
 use bevy::image::TextureFormatPixelInfo;
+use bevy::pbr::wireframe::WireframeConfig;
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureFormat, TextureUsages};
 use bevy::render::renderer::RenderDevice;
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
 
-use crate::cleanup::cleanup_old_sessions;
-use crate::config::ScreenshotConfig;
+use crate::camera_poses::{CAMERA_POSES_FILE, CameraPose, CameraPoseFile};
+use crate::cleanup::plan_cleanup;
+use crate::config::{CaptureMode, ScreenshotConfig};
+use crate::contact_sheet::{CONTACT_SHEET_FILE, build_contact_sheet};
 use crate::image_copy::{ImageCopier, ImageToSave, MainWorldReceiver};
+use crate::manifest::{
+	CaptureRecord, MANIFEST_FILE, PresetTiming, SessionManifest, SessionTiming, WAVE_MAP_PRESET,
+	WaveMapMapping,
+};
 use crate::plugin::HarnessCameraReady;
 use crate::presets::CameraPreset;
-use crate::state::{SETTLE_FRAMES, ScreenshotPhase, ScreenshotState};
+use crate::processing::{
+	apply_color_grade, crop_to_region, downsample_box, mean_abs_difference, save_image, unpad_rows,
+};
+use crate::state::{
+	CAPTURE_FAILED_EXIT_CODE, CaptureProgress, ResultSender, ScreenshotPhase, ScreenshotResults,
+	ScreenshotState, unix_millis,
+};
 
 /// Marker component for the main camera
 #[derive(Component)]
 pub struct MainCamera;
 
-/// Sets up the camera and render target.
+/// A harness camera rendering one preset into its own render target.
+///
+/// In sequential mode there is a single view whose preset advances after each capture;
+/// in simultaneous mode there is one view per preset.
+#[derive(Component)]
+pub(crate) struct CaptureView {
+	pub preset: usize,
+}
+
+/// Preset used when the config provides none
+const FALLBACK_PRESET: CameraPreset = CameraPreset::polar("default", 50.0, 20.0, 0.0);
+
+/// Sets up the capture cameras and their render targets.
 pub fn setup_camera(
 	mut commands: Commands,
 	mut images: ResMut<Assets<Image>>,
@@ -24,100 +56,225 @@ pub fn setup_camera(
 	mut state: ResMut<ScreenshotState>,
 	render_device: Res<RenderDevice>,
 ) {
-	let preset = config.presets.first().copied().unwrap_or(CameraPreset {
-		name: "default",
-		radius: 50.0,
-		height: 20.0,
-		angle: 0.0,
-		look_offset: Vec3::ZERO,
-	});
-
-	let pos = preset.to_position();
-
-	commands.insert_resource(ClearColor(Color::BLACK));
+	let clear_color = config.clear_color;
+	commands.insert_resource(ClearColor(clear_color));
 
+	let render_size = config.render_size();
 	let size = Extent3d {
-		width: config.width,
-		height: config.height,
+		width: render_size.x,
+		height: render_size.y,
 		..default()
 	};
 
-	let mut render_target_image =
-		Image::new_target_texture(size.width, size.height, TextureFormat::bevy_default(), None);
-	render_target_image.texture_descriptor.usage |= TextureUsages::COPY_SRC;
-	let render_target_handle = images.add(render_target_image);
+	let view_count = match config.capture_mode {
+		CaptureMode::Sequential => 1,
+		CaptureMode::Simultaneous => config.presets.len().max(1),
+	};
 
-	let cpu_image =
-		Image::new_target_texture(size.width, size.height, TextureFormat::bevy_default(), None);
-	let cpu_image_handle = images.add(cpu_image);
+	for index in 0..view_count {
+		let preset = config
+			.presets
+			.get(index)
+			.cloned()
+			.unwrap_or(FALLBACK_PRESET);
 
-	commands.spawn(ImageCopier::new(
-		render_target_handle.clone(),
-		size,
-		&render_device,
-	));
+		let mut render_target_image =
+			Image::new_target_texture(size.width, size.height, TextureFormat::bevy_default(), None);
+		render_target_image.texture_descriptor.usage |= TextureUsages::COPY_SRC;
+		let render_target_handle = images.add(render_target_image);
 
-	commands.spawn(ImageToSave(cpu_image_handle));
-	state.render_target = Some(render_target_handle.clone());
+		let cpu_image =
+			Image::new_target_texture(size.width, size.height, TextureFormat::bevy_default(), None);
+		let cpu_image_handle = images.add(cpu_image);
 
-	commands.spawn((
-		Camera3d::default(),
-		Camera {
-			clear_color: ClearColorConfig::Custom(Color::BLACK),
-			..default()
-		},
-		bevy::camera::RenderTarget::Image(render_target_handle.into()),
-		Transform::from_translation(pos).looking_at(preset.look_offset, Vec3::Y),
-		MainCamera,
-	));
-
-	commands.insert_resource(GlobalAmbientLight {
-		color: Color::WHITE,
-		brightness: 300.0,
-		..default()
-	});
+		if state.render_target.is_none() {
+			state.render_target = Some(render_target_handle.clone());
+		}
 
-	commands.spawn((
-		DirectionalLight {
-			illuminance: 10000.0,
-			shadows_enabled: true,
-			..default()
-		},
-		Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.8, 0.4, 0.0)),
-	));
+		commands.spawn((
+			Camera3d::default(),
+			Camera {
+				clear_color: ClearColorConfig::Custom(clear_color),
+				..default()
+			},
+			bevy::camera::RenderTarget::Image(render_target_handle.clone().into()),
+			preset.transform(),
+			config.projection(&preset),
+			config.msaa,
+			config.tonemapping,
+			if config.deband_dither {
+				DebandDither::Enabled
+			} else {
+				DebandDither::Disabled
+			},
+			MainCamera,
+			CaptureView { preset: index },
+			ImageCopier::new(
+				render_target_handle,
+				size,
+				TextureFormat::bevy_default(),
+				&render_device,
+			),
+			ImageToSave(cpu_image_handle),
+		));
+	}
 
 	commands.insert_resource(HarnessCameraReady);
 }
 
-pub(crate) fn prepare_screenshot_dir(config: Res<ScreenshotConfig>, state: Res<ScreenshotState>) {
-	let _ = std::fs::create_dir_all(config.screenshot_dir().join(&state.session_dir));
-	cleanup_old_sessions(&config.screenshot_dir(), config.retain_sessions);
+/// Spawns the harness's directional light and sets the ambient light, if configured
+pub(crate) fn setup_lighting(mut commands: Commands, config: Res<ScreenshotConfig>) {
+	if let Some(lighting) = &config.lighting {
+		commands.insert_resource(GlobalAmbientLight {
+			color: lighting.ambient_color,
+			brightness: lighting.ambient_brightness,
+			..default()
+		});
+
+		commands.spawn((
+			DirectionalLight {
+				illuminance: lighting.illuminance,
+				shadows_enabled: lighting.shadows,
+				..default()
+			},
+			Transform::default().looking_to(lighting.direction, Vec3::Y),
+		));
+	}
+}
+
+pub(crate) fn prepare_screenshot_dir(
+	config: Res<ScreenshotConfig>,
+	mut state: ResMut<ScreenshotState>,
+) {
+	state.phase = ScreenshotPhase::Init(config.pre_roll_frames);
+	state.started_at = Instant::now();
+	state.preset_started_at = state.started_at;
+	let screenshot_dir = config.screenshot_dir();
+
+	if let Some(name) = &config.session_name {
+		// A sanitized name is a single path component, so only an empty one could point
+		// the removal at the screenshots directory itself
+		let name = sanitize_dir_name(name);
+		if name.is_empty() {
+			warn!("Session name is empty, using a timestamped session directory");
+		} else {
+			let _ = std::fs::remove_dir_all(screenshot_dir.join(&name));
+			state.session_dir = name;
+		}
+	} else if let Some(label) = &config.session_label {
+		state.session_dir = format!("{}_{}", state.session_dir, sanitize_dir_name(label));
+	}
+
+	let session_path = screenshot_dir.join(&state.session_dir);
+	let _ = std::fs::create_dir_all(&session_path);
+
+	// A session name with a numeric prefix parses as a timestamp, so the session being
+	// written must be kept out of the cleanup explicitly.
+	let stale = plan_cleanup(&screenshot_dir, config.retain_policy)
+		.into_iter()
+		.filter(|path| *path != session_path);
+	for path in stale {
+		if config.cleanup_dry_run {
+			info!("Cleanup dry run: would remove {}", path.display());
+		} else {
+			let _ = std::fs::remove_dir_all(path);
+		}
+	}
+}
+
+/// Replaces anything but ASCII alphanumerics and `-` with `_` so user-provided
+/// names stay a single path component.
+fn sanitize_dir_name(name: &str) -> String {
+	name.chars()
+		.map(|c| {
+			if c.is_ascii_alphanumeric() || c == '-' {
+				c
+			} else {
+				'_'
+			}
+		})
+		.collect()
+}
+
+/// Counts the frames of an unfinished sequence and aborts the app with an error once
+/// it runs `max_frames` past [`ScreenshotConfig::expected_frames`].
+pub(crate) fn frame_watchdog(
+	config: Res<ScreenshotConfig>,
+	mut state: ResMut<ScreenshotState>,
+	mut app_exit: MessageWriter<AppExit>,
+) {
+	if state.phase == ScreenshotPhase::Done || state.aborted {
+		return;
+	}
+
+	state.frames_elapsed += 1;
+	let limit = config.expected_frames().saturating_add(config.max_frames);
+	if config.max_frames > 0 && state.frames_elapsed > limit {
+		error!(
+			frames = state.frames_elapsed,
+			limit,
+			phase = ?state.phase,
+			"Screenshot sequence did not finish, aborting"
+		);
+		state.aborted = true;
+		app_exit.write(AppExit::error());
+	}
 }
 
 pub(crate) fn screenshot_sequence(
 	config: Res<ScreenshotConfig>,
 	mut state: ResMut<ScreenshotState>,
-	mut camera: Query<&mut Transform, With<MainCamera>>,
+	mut views: Query<
+		(
+			&mut CaptureView,
+			&mut Transform,
+			&mut Projection,
+			&ImageCopier,
+			&ImageToSave,
+		),
+		With<MainCamera>,
+	>,
 	receiver: Res<MainWorldReceiver>,
-	images_to_save: Query<&ImageToSave>,
 	mut images: ResMut<Assets<Image>>,
+	mut wireframe: Option<ResMut<WireframeConfig>>,
+	mut progress: ResMut<CaptureProgress>,
 	mut app_exit: MessageWriter<AppExit>,
 ) {
+	if state.aborted {
+		return;
+	}
+
 	match &state.phase {
 		ScreenshotPhase::Init(frames_remaining) => {
 			while receiver.try_recv().is_ok() {}
 
-			if *frames_remaining == 0 {
+			if *frames_remaining > 0 {
+				state.phase = ScreenshotPhase::Init(frames_remaining - 1);
+			} else if !config.wait_for_assets || state.assets_ready {
+				state.phase = ScreenshotPhase::Capturing;
+			} else if state.asset_wait_frames >= config.asset_timeout_frames {
+				warn!(
+					frames = state.asset_wait_frames,
+					"Tracked assets still loading, capturing anyway"
+				);
 				state.phase = ScreenshotPhase::Capturing;
 			} else {
-				state.phase = ScreenshotPhase::Init(frames_remaining - 1);
+				state.asset_wait_frames += 1;
 			}
 		}
 
 		ScreenshotPhase::Settling(frames_remaining) => {
-			while receiver.try_recv().is_ok() {}
+			let frames_remaining = *frames_remaining;
+			let settled = match config.settle_threshold {
+				Some(threshold) => frames_settled(&receiver, &mut state.previous_frames, threshold),
+				None => {
+					while receiver.try_recv().is_ok() {}
+					false
+				}
+			};
 
-			if *frames_remaining == 0 {
+			if settled || frames_remaining == 0 {
+				state.previous_frames.clear();
 				state.phase = ScreenshotPhase::Capturing;
 			} else {
 				state.phase = ScreenshotPhase::Settling(frames_remaining - 1);
@@ -125,81 +282,961 @@ pub(crate) fn screenshot_sequence(
 		}
 
 		ScreenshotPhase::Capturing => {
-			let mut image_data = Vec::new();
-			while let Ok(data) = receiver.try_recv() {
-				image_data = data;
+			let mut frames = HashMap::new();
+			while let Ok(result) = receiver.try_recv() {
+				match result {
+					Ok(copied) => {
+						frames.insert(copied.source, copied.data);
+					}
+					Err(e) => warn!(%e, "Skipping frame after failed readback"),
+				}
 			}
 
-			if image_data.is_empty() {
+			// Wait until every view has delivered a frame so all presets are captured together
+			if views
+				.iter()
+				.any(|(_, _, _, copier, _)| !frames.contains_key(&copier.source().id()))
+			{
 				return;
 			}
 
-			for image_to_save in images_to_save.iter() {
-				let img_bytes = images.get_mut(image_to_save.id()).unwrap();
-				let row_bytes = img_bytes.width() as usize
-					* img_bytes.texture_descriptor.format.pixel_size().unwrap();
-				let aligned_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+			let wireframe_pass = state.wireframe_pass;
+			for (view, transform, _, copier, image_to_save) in views.iter() {
+				let Some(image_data) = frames.remove(&copier.source().id()) else {
+					continue;
+				};
 
-				if row_bytes == aligned_row_bytes {
-					img_bytes.data.as_mut().unwrap().clone_from(&image_data);
-				} else {
-					img_bytes.data = Some(
-						image_data
-							.chunks(aligned_row_bytes)
-							.take(img_bytes.height() as usize)
-							.flat_map(|row| &row[..row_bytes.min(row.len())])
-							.cloned()
-							.collect(),
-					);
-				}
+				let img_bytes = images.get_mut(image_to_save.id()).unwrap();
+				img_bytes.data = Some(unpad_rows(
+					&image_data,
+					img_bytes.width() as usize,
+					img_bytes.height() as usize,
+					img_bytes.texture_descriptor.format.pixel_size().unwrap(),
+				));
 
+				let preset = &config.presets[view.preset];
 				let img = match img_bytes.clone().try_into_dynamic() {
-					Ok(img) => img.to_rgba8(),
+					Ok(img) => postprocess(img.to_rgba8(), &config, preset),
 					Err(e) => {
 						error!("Failed to create image buffer: {e:?}");
+						state.failed_captures += 1;
 						continue;
 					}
 				};
 
-				let preset = &config.presets[state.current_preset];
+				let suffix = if wireframe_pass { "_wire" } else { "" };
+				let stem = capture_stem(&config, preset, view.preset, &state.session_dir);
 				let path = config
 					.screenshot_dir()
 					.join(&state.session_dir)
-					.join(format!("{}.png", preset.name));
+					.join(format!(
+						"{stem}{suffix}.{}",
+						config.output_format.extension()
+					));
 
 				if let Some(parent) = path.parent() {
 					let _ = std::fs::create_dir_all(parent);
 				}
 
-				match img.save(&path) {
-					Ok(()) => state.captured_paths.push(path.display().to_string()),
-					Err(e) => error!(%e, ?path, "Failed to save screenshot"),
+				match save_capture(&img, &path, &config) {
+					Ok(paths) => {
+						state.captures.push(CaptureRecord {
+							preset: preset.name.to_string(),
+							path: paths[0].clone(),
+							width: img.width(),
+							height: img.height(),
+							extra_paths: paths[1..].to_vec(),
+							camera_position: transform.translation.to_array(),
+							look_target: preset.look_target().to_array(),
+							timestamp_ms: unix_millis(),
+							wireframe: wireframe_pass,
+						});
+						state.captured_paths.extend(paths);
+					}
+					Err(e) => {
+						error!(%e, ?path, "Failed to save screenshot");
+						state.failed_captures += 1;
+					}
+				}
+			}
+
+			// Re-capture the same preset with the global wireframe overlay before moving on
+			if let Some(wireframe) = wireframe.as_mut()
+				&& config.capture_wireframe
+			{
+				state.wireframe_pass = !state.wireframe_pass;
+				wireframe.global = state.wireframe_pass;
+				if state.wireframe_pass {
+					// Simultaneous views settle together, so wait for the slowest preset
+					let settle_frames = views
+						.iter()
+						.map(|(view, ..)| config.preset_settle_frames(&config.presets[view.preset]))
+						.max()
+						.unwrap_or(config.settle_frames);
+					state.phase = ScreenshotPhase::Settling(settle_frames);
+					return;
 				}
 			}
 
-			if config.multi_shot && state.current_preset + 1 < config.presets.len() {
-				state.current_preset += 1;
-				let preset = &config.presets[state.current_preset];
-				if let Ok(mut transform) = camera.single_mut() {
-					let pos = preset.to_position();
-					transform.translation = pos;
-					transform.look_at(preset.look_offset, Vec3::Y);
+			progress.completed += views.iter().len();
+			info!("captured {}/{}", progress.completed, progress.total);
+
+			let frames = state.frames_elapsed - state.preset_start_frame;
+			let duration_ms = millis(state.preset_started_at.elapsed());
+			for (view, ..) in views.iter() {
+				state.preset_timings.push(PresetTiming {
+					preset: config.presets[view.preset].name.to_string(),
+					frames,
+					duration_ms,
+				});
+			}
+			state.preset_start_frame = state.frames_elapsed;
+			state.preset_started_at = Instant::now();
+
+			let next_preset = state.current_preset + 1;
+			let advance = config.capture_mode == CaptureMode::Sequential
+				&& config.multi_shot
+				&& next_preset < config.presets.len();
+
+			if advance {
+				state.current_preset = next_preset;
+				let preset = &config.presets[next_preset];
+				for (mut view, mut transform, mut projection, _, _) in &mut views {
+					view.preset = next_preset;
+					*transform = preset.transform();
+					*projection = config.projection(preset);
 				}
 
-				state.phase = ScreenshotPhase::Settling(SETTLE_FRAMES);
+				state.phase = ScreenshotPhase::Settling(config.preset_settle_frames(preset));
 			} else {
 				state.phase = ScreenshotPhase::Done;
+				state.total_duration = Some(state.started_at.elapsed());
 			}
 		}
 
 		ScreenshotPhase::Done => {
 			if config.exit_after {
-				for path in &state.captured_paths {
-					info!(path, "saved");
+				if config.agent_mode {
+					print_agent_summary(&state.captures);
+				} else {
+					log_capture_table(&state.captures);
 				}
-				info!("Agents: Read screenshots ONE AT A TIME. Analyze before proceeding.");
-				app_exit.write(AppExit::Success);
+				if let Some(instructions) = &config.agent_instructions {
+					info!("{instructions}");
+				}
+
+				let captured = state
+					.captures
+					.iter()
+					.filter(|capture| !capture.wireframe)
+					.count();
+				if state.failed_captures > 0 || captured < progress.total {
+					error!(
+						failed = state.failed_captures,
+						captured,
+						expected = progress.total,
+						"Some captures failed"
+					);
+					app_exit.write(AppExit::from_code(CAPTURE_FAILED_EXIT_CODE));
+				} else {
+					app_exit.write(AppExit::Success);
+				}
+			}
+		}
+	}
+}
+
+/// Drains the pending readbacks and reports whether every view changed by less than
+/// `threshold` since its previous frame.
+fn frames_settled(
+	receiver: &MainWorldReceiver,
+	previous_frames: &mut HashMap<AssetId<Image>, Vec<u8>>,
+	threshold: f32,
+) -> bool {
+	let mut received = false;
+	let mut settled = true;
+
+	while let Ok(result) = receiver.try_recv() {
+		let Ok(copied) = result else {
+			continue;
+		};
+
+		received = true;
+		let difference = previous_frames
+			.get(&copied.source)
+			.and_then(|previous| mean_abs_difference(previous, &copied.data));
+		settled &= difference.is_some_and(|difference| difference < threshold);
+		previous_frames.insert(copied.source, copied.data);
+	}
+
+	received && settled
+}
+
+/// Applies the configured flip, supersample downsampling, crop and color grade to a
+/// captured frame of `preset`.
+///
+/// The wave map is neither flipped nor cropped, so its pixels keep the world mapping
+/// recorded in the manifest.
+fn postprocess(mut img: RgbaImage, config: &ScreenshotConfig, preset: &CameraPreset) -> RgbaImage {
+	let wave_map = preset.name == WAVE_MAP_PRESET;
+
+	if config.flip_vertical && !wave_map {
+		imageops::flip_vertical_in_place(&mut img);
+	}
+
+	if config.supersample > 1 {
+		img = downsample_box(&img, config.supersample);
+	}
+
+	if let Some(region) = config.capture_region.filter(|_| !wave_map) {
+		match crop_to_region(&img, region) {
+			Some(cropped) => img = cropped,
+			None => warn!(?region, "Capture region is empty, saving full frame"),
+		}
+	}
+
+	apply_color_grade(&mut img, &config.color_grade);
+	img
+}
+
+/// File stem for a capture of the preset at `index`.
+///
+/// The wave map is always saved as `wave_map`, ignoring the filename template.
+fn capture_stem(
+	config: &ScreenshotConfig,
+	preset: &CameraPreset,
+	index: usize,
+	session: &str,
+) -> String {
+	if preset.name == WAVE_MAP_PRESET {
+		WAVE_MAP_PRESET.to_string()
+	} else {
+		config.capture_file_stem(&preset.name, index, session)
+	}
+}
+
+/// Saves `img` to `path` followed by its extra resolutions, returning the written paths
+/// with the full-size capture first.
+fn save_capture(
+	img: &RgbaImage,
+	path: &Path,
+	config: &ScreenshotConfig,
+) -> image::ImageResult<Vec<String>> {
+	save_image(img, path, config.output_format)?;
+
+	let mut paths = vec![path.display().to_string()];
+	paths.extend(save_extra_resolutions(img, path, config));
+	Ok(paths)
+}
+
+/// Writes `{stem}@{w}x{h}` copies of a saved capture at each extra resolution.
+///
+/// Scales the already processed image, so the primary capture is unaffected.
+fn save_extra_resolutions(img: &RgbaImage, path: &Path, config: &ScreenshotConfig) -> Vec<String> {
+	let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+	let extension = config.output_format.extension();
+
+	config
+		.extra_resolutions
+		.iter()
+		.filter_map(|&(width, height)| {
+			let resized = imageops::resize(img, width, height, FilterType::Lanczos3);
+			let extra_path = path.with_file_name(format!("{stem}@{width}x{height}.{extension}"));
+			match save_image(&resized, &extra_path, config.output_format) {
+				Ok(()) => Some(extra_path.display().to_string()),
+				Err(e) => {
+					error!(%e, path = ?extra_path, "Failed to save resized screenshot");
+					None
+				}
+			}
+		})
+		.collect()
+}
+
+/// Prints the captures as one JSON document on stdout for automation.
+fn print_agent_summary(captures: &[CaptureRecord]) {
+	match serde_json::to_string(captures) {
+		Ok(json) => println!("{json}"),
+		Err(e) => error!(%e, "Failed to serialize capture summary"),
+	}
+}
+
+/// Logs a compact preset / resolution / path table of the captures.
+fn log_capture_table(captures: &[CaptureRecord]) {
+	let rows: Vec<_> = captures
+		.iter()
+		.map(|capture| {
+			let preset = if capture.wireframe {
+				format!("{}_wire", capture.preset)
+			} else {
+				capture.preset.clone()
+			};
+			let resolution = format!("{}x{}", capture.width, capture.height);
+			(preset, resolution, capture.path.as_str())
+		})
+		.collect();
+
+	let preset_width = rows
+		.iter()
+		.map(|(preset, ..)| preset.len())
+		.max()
+		.unwrap_or(0);
+	let resolution_width = rows.iter().map(|(_, res, _)| res.len()).max().unwrap_or(0);
+
+	for (preset, resolution, path) in &rows {
+		info!("{preset:<preset_width$}  {resolution:>resolution_width$}  {path}");
+	}
+}
+
+/// Runs once as the sequence enters the `Done` phase.
+///
+/// Writes the session manifest, then publishes [`ScreenshotResults`] as a resource and
+/// over the caller's result channel, if one was provided.
+pub(crate) fn finish_session(
+	mut commands: Commands,
+	config: Res<ScreenshotConfig>,
+	state: Res<ScreenshotState>,
+	sender: Option<Res<ResultSender>>,
+) {
+	let session_dir = config.screenshot_dir().join(&state.session_dir);
+	write_manifest(&config, &state);
+
+	if config.contact_sheet {
+		write_contact_sheet(&state, &session_dir);
+	}
+
+	if config.camera_poses {
+		write_camera_poses(&config, &state, &session_dir);
+	}
+
+	let results = ScreenshotResults {
+		session_dir,
+		captured_paths: state.captured_paths.iter().map(PathBuf::from).collect(),
+	};
+
+	if let Some(sender) = sender {
+		let _ = sender.send(results.clone());
+	}
+
+	commands.insert_resource(results);
+}
+
+fn write_manifest(config: &ScreenshotConfig, state: &ScreenshotState) {
+	let manifest = SessionManifest {
+		example_name: config.example_name.clone(),
+		session: state.session_dir.clone(),
+		width: config.width,
+		height: config.height,
+		wave_count: config.wave_count,
+		captures: state.captures.clone(),
+		wave_map: config
+			.wave_map
+			.filter(|_| {
+				state
+					.captures
+					.iter()
+					.any(|capture| capture.preset == WAVE_MAP_PRESET)
+			})
+			.map(|area| WaveMapMapping::new(area, config.width, config.height)),
+		timing: SessionTiming {
+			total_ms: millis(
+				state
+					.total_duration
+					.unwrap_or_else(|| state.started_at.elapsed()),
+			),
+			frames: state.frames_elapsed,
+			presets: state.preset_timings.clone(),
+		},
+	};
+
+	let path = config
+		.screenshot_dir()
+		.join(&state.session_dir)
+		.join(MANIFEST_FILE);
+
+	if let Err(e) = manifest.write_atomic(&path) {
+		error!(%e, ?path, "Failed to write session manifest");
+	}
+}
+
+/// Duration in fractional milliseconds
+fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+/// Writes the poses of the captured presets for use in external tools.
+fn write_camera_poses(config: &ScreenshotConfig, state: &ScreenshotState, session_dir: &Path) {
+	let captured = config
+		.presets
+		.iter()
+		.filter(|preset| {
+			state
+				.captures
+				.iter()
+				.any(|capture| capture.preset == preset.name)
+		})
+		.map(|preset| CameraPose::with_projection(preset, &config.projection(preset)));
+	let poses = CameraPoseFile::new(captured, config.width, config.height);
+
+	let path = session_dir.join(CAMERA_POSES_FILE);
+	if let Err(e) = poses.write(&path) {
+		error!(%e, ?path, "Failed to write camera poses");
+	}
+}
+
+/// Loads the saved captures back and tiles them into a contact sheet.
+fn write_contact_sheet(state: &ScreenshotState, session_dir: &Path) {
+	let cells: Vec<_> = state
+		.captures
+		.iter()
+		.filter_map(|capture| match image::open(&capture.path) {
+			Ok(img) => {
+				let label = if capture.wireframe {
+					format!("{}_wire", capture.preset)
+				} else {
+					capture.preset.clone()
+				};
+				Some((label, img.to_rgba8()))
 			}
+			Err(e) => {
+				warn!(%e, path = capture.path, "Skipping capture in contact sheet");
+				None
+			}
+		})
+		.collect();
+
+	let Some(sheet) = build_contact_sheet(&cells) else {
+		return;
+	};
+
+	let path = session_dir.join(CONTACT_SHEET_FILE);
+	match sheet.save(&path) {
+		Ok(()) => info!(path = %path.display(), "contact sheet"),
+		Err(e) => error!(%e, ?path, "Failed to save contact sheet"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use bevy::ecs::system::RunSystemOnce;
+	use crossbeam_channel::Sender;
+
+	use super::*;
+	use crate::cleanup::RetainPolicy;
+	use crate::config::{LightingConfig, MAX_FRAMES};
+	use crate::image_copy::{CopiedImage, ImageCopyError, ImageCopyResult};
+
+	/// App running only the watchdog, with the sequence stuck waiting for image data
+	fn stalled_app(config: ScreenshotConfig) -> App {
+		let mut app = App::new();
+		app.add_message::<AppExit>()
+			.insert_resource(config)
+			.insert_resource(ScreenshotState {
+				phase: ScreenshotPhase::Capturing,
+				..default()
+			})
+			.add_systems(Update, frame_watchdog);
+		app
+	}
+
+	/// App running the screenshot sequence, with the sender feeding it readback results.
+	///
+	/// Without capture views the sequence steps through every preset without image data.
+	fn sequence_app(config: ScreenshotConfig) -> (App, Sender<ImageCopyResult>) {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+
+		let mut app = App::new();
+		app.add_message::<AppExit>()
+			.insert_resource(config)
+			.init_resource::<ScreenshotState>()
+			.init_resource::<CaptureProgress>()
+			.init_resource::<Assets<Image>>()
+			.insert_resource(MainWorldReceiver(receiver))
+			.add_systems(Startup, prepare_screenshot_dir)
+			.add_systems(Update, screenshot_sequence);
+		(app, sender)
+	}
+
+	/// Spawns a capture view of `preset`, returning the render target its copier reads.
+	///
+	/// The copier's readback buffer lives on a noop wgpu device, so no GPU is needed.
+	fn spawn_capture_view(app: &mut App, preset: usize) -> AssetId<Image> {
+		let (device, _queue) = wgpu::Device::noop(&default());
+		let render_device = RenderDevice::from(device);
+		let size = Extent3d {
+			width: 4,
+			height: 2,
+			..default()
+		};
+
+		let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+		let target = images.add(Image::new_target_texture(
+			size.width,
+			size.height,
+			TextureFormat::bevy_default(),
+			None,
+		));
+		let cpu_image = images.add(Image::new_target_texture(
+			size.width,
+			size.height,
+			TextureFormat::bevy_default(),
+			None,
+		));
+
+		let source = target.id();
+		app.world_mut().spawn((
+			MainCamera,
+			CaptureView { preset },
+			Transform::default(),
+			Projection::default(),
+			ImageCopier::new(target, size, TextureFormat::bevy_default(), &render_device),
+			ImageToSave(cpu_image),
+		));
+		source
+	}
+
+	#[test]
+	fn failed_map_keeps_the_sequence_waiting() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_pre_roll_frames(0)
+			.with_settle_frames(0);
+		let (mut app, sender) = sequence_app(config);
+		spawn_capture_view(&mut app, 0);
+
+		app.update();
+		assert_eq!(
+			app.world().resource::<ScreenshotState>().phase,
+			ScreenshotPhase::Capturing
+		);
+
+		for _ in 0..5 {
+			sender
+				.send(Err(ImageCopyError::Map("device lost".into())))
+				.unwrap();
+			app.update();
+		}
+
+		let state = app.world().resource::<ScreenshotState>();
+		assert_eq!(state.phase, ScreenshotPhase::Capturing);
+		assert!(state.captures.is_empty());
+		assert!(state.captured_paths.is_empty());
+		assert_eq!(state.failed_captures, 0);
+	}
+
+	#[test]
+	fn simultaneous_wireframe_pass_waits_for_slowest_preset() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_presets(vec![
+				CameraPreset::polar("quick", 10.0, 5.0, 0.0).with_settle_frames(2),
+				CameraPreset::polar("slow", 10.0, 5.0, 1.0).with_settle_frames(6),
+			])
+			.with_simultaneous_capture()
+			.with_wireframe_capture()
+			.with_pre_roll_frames(0)
+			.with_settle_frames(0);
+		let (mut app, sender) = sequence_app(config);
+		app.init_resource::<WireframeConfig>();
+		let sources = [
+			spawn_capture_view(&mut app, 0),
+			spawn_capture_view(&mut app, 1),
+		];
+
+		// Init, then the shaded capture of both views
+		app.update();
+		for source in sources {
+			let data = vec![0; RenderDevice::align_copy_bytes_per_row(4 * 4) * 2];
+			sender.send(Ok(CopiedImage { source, data })).unwrap();
+		}
+		app.update();
+
+		let state = app.world().resource::<ScreenshotState>();
+		assert_eq!(state.captures.len(), 2);
+		assert!(state.wireframe_pass);
+		assert_eq!(state.phase, ScreenshotPhase::Settling(6));
+		assert!(app.world().resource::<WireframeConfig>().global);
+	}
+
+	#[test]
+	fn preset_settle_override_is_honored() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_presets(vec![
+				CameraPreset::polar("default", 10.0, 5.0, 0.0),
+				CameraPreset::polar("slow", 10.0, 5.0, 1.0).with_settle_frames(7),
+			])
+			.with_pre_roll_frames(0)
+			.with_settle_frames(2);
+		let (mut app, _sender) = sequence_app(config);
+
+		let mut phases = Vec::new();
+		for _ in 0..20 {
+			app.update();
+			let state = app.world().resource::<ScreenshotState>();
+			phases.push((state.current_preset, state.phase.clone()));
+			if state.phase == ScreenshotPhase::Done {
+				break;
+			}
+		}
+
+		// Settling(7) down to Settling(0) on the preset with the override
+		let settling = phases
+			.iter()
+			.filter(|(current, phase)| {
+				*current == 1 && matches!(phase, ScreenshotPhase::Settling(_))
+			})
+			.count();
+		assert!(phases.contains(&(1, ScreenshotPhase::Settling(7))));
+		assert_eq!(settling, 8);
+		assert_eq!(phases.last(), Some(&(1, ScreenshotPhase::Done)));
+	}
+
+	#[test]
+	fn session_records_elapsed_time() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_pre_roll_frames(2)
+			.with_settle_frames(0);
+		let (mut app, _sender) = sequence_app(config);
+
+		for _ in 0..10 {
+			app.update();
+			std::thread::sleep(Duration::from_millis(1));
+			if app.world().resource::<ScreenshotState>().phase == ScreenshotPhase::Done {
+				break;
+			}
+		}
+
+		let state = app.world().resource::<ScreenshotState>();
+		assert_eq!(state.phase, ScreenshotPhase::Done);
+		let total = state.total_duration.expect("total duration not recorded");
+		assert!(total >= Duration::from_millis(2), "{total:?}");
+
+		app.world_mut().run_system_once(finish_session).unwrap();
+		let session_dir = app
+			.world()
+			.resource::<ScreenshotResults>()
+			.session_dir
+			.clone();
+		let manifest: serde_json::Value =
+			serde_json::from_slice(&std::fs::read(session_dir.join(MANIFEST_FILE)).unwrap())
+				.unwrap();
+		let total_ms = manifest["timing"]["total_ms"].as_f64().unwrap();
+		assert!(
+			(total_ms - millis(total)).abs() < 1e-6,
+			"{total_ms} != {total:?}"
+		);
+	}
+
+	#[test]
+	fn spawned_light_uses_configured_illuminance() {
+		let config = ScreenshotConfig::default().with_lighting(LightingConfig {
+			illuminance: 3200.0,
+			..default()
+		});
+
+		let mut app = App::new();
+		app.insert_resource(config)
+			.add_systems(Update, setup_lighting);
+		app.update();
+
+		let mut lights = app.world_mut().query::<&DirectionalLight>();
+		let illuminances: Vec<f32> = lights
+			.iter(app.world())
+			.map(|light| light.illuminance)
+			.collect();
+		assert_eq!(illuminances, [3200.0]);
+	}
+
+	#[test]
+	fn no_light_without_lighting_config() {
+		let mut app = App::new();
+		app.insert_resource(ScreenshotConfig::default().without_default_lighting())
+			.add_systems(Update, setup_lighting);
+		app.update();
+
+		let mut lights = app.world_mut().query::<&DirectionalLight>();
+		assert_eq!(lights.iter(app.world()).count(), 0);
+		assert!(!app.world().contains_resource::<GlobalAmbientLight>());
+	}
+
+	#[test]
+	fn cleanup_keeps_numeric_session_name() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_session_name("42_nightly")
+			.with_retain_policy(RetainPolicy::Age(Duration::from_secs(3600)));
+		let screenshot_dir = config.screenshot_dir();
+		std::fs::create_dir_all(screenshot_dir.join("7")).unwrap();
+
+		let mut app = App::new();
+		app.insert_resource(config)
+			.init_resource::<ScreenshotState>()
+			.add_systems(Update, prepare_screenshot_dir);
+		app.update();
+
+		assert!(screenshot_dir.join("42_nightly").is_dir());
+		assert!(!screenshot_dir.join("7").exists());
+	}
+
+	fn copied(source: AssetId<Image>, data: Vec<u8>) -> ImageCopyResult {
+		Ok(CopiedImage { source, data })
+	}
+
+	#[test]
+	fn frames_settle_below_threshold() {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		let receiver = MainWorldReceiver(receiver);
+		let mut previous = HashMap::new();
+		let source = AssetId::<Image>::default();
+
+		// The first frame has nothing to compare against
+		sender.send(copied(source, vec![100; 16])).unwrap();
+		assert!(!frames_settled(&receiver, &mut previous, 2.0));
+
+		// Mean difference 4.0 is above the threshold
+		sender.send(copied(source, vec![104; 16])).unwrap();
+		assert!(!frames_settled(&receiver, &mut previous, 2.0));
+
+		// Mean difference 1.0 is below it
+		sender.send(copied(source, vec![105; 16])).unwrap();
+		assert!(frames_settled(&receiver, &mut previous, 2.0));
+
+		// A frame without data never counts as settled
+		assert!(!frames_settled(&receiver, &mut previous, 2.0));
+	}
+
+	#[test]
+	fn failed_readback_does_not_settle() {
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		let receiver = MainWorldReceiver(receiver);
+		let mut previous = HashMap::new();
+		let source = AssetId::<Image>::default();
+		previous.insert(source, vec![100; 16]);
+
+		sender
+			.send(Err(ImageCopyError::Map("lost device".into())))
+			.unwrap();
+		assert!(!frames_settled(&receiver, &mut previous, 2.0));
+	}
+
+	#[test]
+	fn vertical_flip_swaps_rows() {
+		let img = RgbaImage::from_fn(3, 2, |_, y| image::Rgba([y as u8 * 200, 0, 0, 255]));
+
+		let unflipped = postprocess(img.clone(), &ScreenshotConfig::default(), &FALLBACK_PRESET);
+		assert_eq!(unflipped, img);
+
+		let flipped = postprocess(
+			img,
+			&ScreenshotConfig::default().with_vertical_flip(),
+			&FALLBACK_PRESET,
+		);
+		for x in 0..3 {
+			assert_eq!(flipped.get_pixel(x, 0).0, [200, 0, 0, 255]);
+			assert_eq!(flipped.get_pixel(x, 1).0, [0, 0, 0, 255]);
+		}
+	}
+
+	#[test]
+	fn supersampled_capture_saves_at_output_size() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_resolution(64, 36)
+			.with_supersample(3);
+		let render_size = config.render_size();
+		assert_eq!(render_size, UVec2::new(192, 108));
+
+		let rendered = RgbaImage::new(render_size.x, render_size.y);
+		let path = dir.path().join("front.png");
+		save_capture(
+			&postprocess(rendered, &config, &FALLBACK_PRESET),
+			&path,
+			&config,
+		)
+		.unwrap();
+
+		assert_eq!(image::image_dimensions(&path).unwrap(), (64, 36));
+	}
+
+	#[test]
+	fn wave_map_keeps_its_full_unflipped_frame() {
+		let area = Rect::new(-10.0, -5.0, 10.0, 5.0);
+		let config = ScreenshotConfig::default()
+			.with_resolution(4, 2)
+			.with_wave_map(area)
+			.with_vertical_flip()
+			.with_capture_region(URect::new(0, 0, 2, 1))
+			.with_filename_template("{example}_{index}");
+		let wave_map = CameraPreset::top_down(WAVE_MAP_PRESET, area, 50.0);
+		let img = RgbaImage::from_fn(4, 2, |_, y| image::Rgba([y as u8 * 200, 0, 0, 255]));
+
+		let processed = postprocess(img.clone(), &config, &wave_map);
+		assert_eq!(processed, img);
+		assert_eq!(capture_stem(&config, &wave_map, 3, "1"), WAVE_MAP_PRESET);
+
+		let front = CameraPreset::polar("front", 10.0, 5.0, 0.0);
+		assert_eq!(postprocess(img, &config, &front).dimensions(), (2, 1));
+		assert_ne!(capture_stem(&config, &front, 0, "1"), "front");
+	}
+
+	#[test]
+	fn capture_saves_primary_path_first() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default().with_extra_resolutions(vec![(8, 4), (2, 1)]);
+		let img = RgbaImage::new(16, 8);
+		let path = dir.path().join("front.png");
+
+		let paths = save_capture(&img, &path, &config).unwrap();
+
+		assert_eq!(paths[0], path.display().to_string());
+		assert_eq!(paths.len(), 3);
+		for (path, size) in paths.iter().zip([(16, 8), (8, 4), (2, 1)]) {
+			assert_eq!(image::image_dimensions(path).unwrap(), size);
+		}
+	}
+
+	#[test]
+	fn empty_session_name_keeps_earlier_sessions() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_session_name("");
+		let screenshot_dir = config.screenshot_dir();
+		std::fs::create_dir_all(screenshot_dir.join("latest")).unwrap();
+
+		let mut app = App::new();
+		app.insert_resource(config)
+			.init_resource::<ScreenshotState>()
+			.add_systems(Update, prepare_screenshot_dir);
+		app.update();
+
+		let session_dir = &app.world().resource::<ScreenshotState>().session_dir;
+		assert!(!session_dir.is_empty());
+		assert!(screenshot_dir.join(session_dir).is_dir());
+		assert!(screenshot_dir.join("latest").is_dir());
+	}
+
+	#[test]
+	fn labeled_session_directory_has_timestamp_prefix() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_session_label("before fix/v2");
+		let screenshot_dir = config.screenshot_dir();
+
+		let mut app = App::new();
+		app.insert_resource(config)
+			.init_resource::<ScreenshotState>()
+			.add_systems(Update, prepare_screenshot_dir);
+		let timestamp = app
+			.world()
+			.resource::<ScreenshotState>()
+			.session_dir
+			.clone();
+		app.update();
+
+		let session_dir = &app.world().resource::<ScreenshotState>().session_dir;
+		assert_eq!(*session_dir, format!("{timestamp}_before_fix_v2"));
+		assert!(timestamp.chars().all(|c| c.is_ascii_digit()));
+		assert!(screenshot_dir.join(session_dir).is_dir());
+	}
+
+	#[test]
+	fn cleanup_dry_run_keeps_stale_sessions() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_retain_policy(RetainPolicy::Count(1))
+			.with_cleanup_dry_run();
+		let screenshot_dir = config.screenshot_dir();
+		for stale in ["1", "2", "3_labeled"] {
+			std::fs::create_dir_all(screenshot_dir.join(stale)).unwrap();
+		}
+
+		let mut app = App::new();
+		app.insert_resource(config)
+			.init_resource::<ScreenshotState>()
+			.add_systems(Update, prepare_screenshot_dir);
+		app.update();
+
+		let session_dir = &app.world().resource::<ScreenshotState>().session_dir;
+		assert!(screenshot_dir.join(session_dir).is_dir());
+		for stale in ["1", "2", "3_labeled"] {
+			assert!(screenshot_dir.join(stale).is_dir(), "{stale} was removed");
+		}
+	}
+
+	#[test]
+	fn watchdog_allows_long_healthy_sequences() {
+		let config = ScreenshotConfig::default()
+			.with_presets(CameraPreset::orbit_ring(72, 10.0, 5.0, Vec3::ZERO))
+			.with_wireframe_capture();
+		assert!(config.expected_frames() > MAX_FRAMES);
+	}
+
+	#[test]
+	fn watchdog_aborts_stalled_sequence_after_slack() {
+		let config = ScreenshotConfig::default()
+			.with_presets(CameraPreset::orbit_ring(3, 10.0, 5.0, Vec3::ZERO))
+			.with_max_frames(10);
+		let limit = config.expected_frames() + 10;
+		let mut app = stalled_app(config);
+
+		for _ in 0..limit {
+			app.update();
+		}
+		assert_eq!(app.should_exit(), None);
+
+		app.update();
+		assert_eq!(app.should_exit(), Some(AppExit::error()));
+	}
+
+	#[test]
+	fn watchdog_aborts_sequence_that_never_receives_a_frame() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ScreenshotConfig::default()
+			.with_output_dir(dir.path())
+			.with_presets(vec![CameraPreset::polar("front", 10.0, 5.0, 0.0)])
+			.with_pre_roll_frames(3)
+			.with_settle_frames(0)
+			.with_max_frames(5);
+		let limit = config.expected_frames() + 5;
+		let (mut app, _sender) = sequence_app(config);
+		app.add_systems(Update, frame_watchdog.before(screenshot_sequence));
+		spawn_capture_view(&mut app, 0);
+
+		for _ in 0..limit {
+			app.update();
+		}
+		assert_eq!(app.should_exit(), None);
+		assert_eq!(
+			app.world().resource::<ScreenshotState>().phase,
+			ScreenshotPhase::Capturing
+		);
+
+		app.update();
+		assert_eq!(app.should_exit(), Some(AppExit::error()));
+		let state = app.world().resource::<ScreenshotState>();
+		assert!(state.aborted);
+		assert!(state.captures.is_empty());
+	}
+
+	#[test]
+	fn watchdog_disabled_with_zero_max_frames() {
+		let config = ScreenshotConfig::default().with_max_frames(0);
+		let limit = config.expected_frames();
+		let mut app = stalled_app(config);
+
+		for _ in 0..limit + 10 {
+			app.update();
 		}
+		assert_eq!(app.should_exit(), None);
 	}
 }
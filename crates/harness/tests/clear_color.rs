@@ -0,0 +1,30 @@
+//! The configured clear color ends up in the captured image.
+
+mod common;
+
+use bevy::prelude::*;
+
+use common::{assert_solid, empty_scene_config, load_only_capture, run_capture};
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn captured_background_is_the_clear_color() {
+	let dir = tempfile::tempdir().unwrap();
+	let config = empty_scene_config(dir.path()).with_clear_color(Color::srgb_u8(51, 102, 153));
+
+	let image = load_only_capture(&run_capture(config));
+
+	assert_eq!(image.dimensions(), (64, 32));
+	assert_solid(&image, [51, 102, 153, 255]);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn transparent_background_keeps_zero_alpha() {
+	let dir = tempfile::tempdir().unwrap();
+	let config = empty_scene_config(dir.path()).with_transparent_background();
+
+	let image = load_only_capture(&run_capture(config));
+
+	assert!(image.pixels().all(|pixel| pixel.0[3] == 0));
+}
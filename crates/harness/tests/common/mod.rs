@@ -0,0 +1,83 @@
+//! Shared setup for the capture tests that render on a real GPU adapter.
+
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use bevy_screenshot_harness::{
+	CameraPreset, ScreenshotConfig, ScreenshotHarnessPlugin, ScreenshotResults, headless_runner,
+};
+use image::RgbaImage;
+
+/// Config for a single small capture of an empty scene into `output_dir`.
+///
+/// Tonemapping, dithering and the harness lights are off, so every pixel of the capture
+/// is exactly the clear color.
+pub fn empty_scene_config(output_dir: &std::path::Path) -> ScreenshotConfig {
+	ScreenshotConfig::default()
+		.with_presets(vec![CameraPreset::polar("empty", 10.0, 5.0, 0.0)])
+		.with_resolution(64, 32)
+		.with_output_dir(output_dir)
+		.with_pre_roll_frames(5)
+		.with_settle_frames(0)
+		.with_max_frames(600)
+		.with_tonemapping(Tonemapping::None)
+		.without_deband_dither()
+		.without_default_lighting()
+}
+
+/// Runs a headless app with the harness until the session completes.
+///
+/// Builds the plugins by hand rather than through `headless_plugins`, which would parse
+/// the test runner's arguments as harness flags.
+pub fn run_capture(config: ScreenshotConfig) -> ScreenshotResults {
+	let (sender, receiver) = crossbeam_channel::bounded(1);
+
+	let mut app = App::new();
+	app.add_plugins(
+		DefaultPlugins
+			.set(WindowPlugin {
+				primary_window: None,
+				exit_condition: ExitCondition::DontExit,
+				..default()
+			})
+			.disable::<WinitPlugin>()
+			.disable::<LogPlugin>(),
+	)
+	.add_plugins(headless_runner())
+	.add_plugins(ScreenshotHarnessPlugin::with_config(config).with_result_sender(sender));
+
+	let exit = app.run();
+	assert!(exit.is_success(), "capture exited with {exit:?}");
+	receiver
+		.try_recv()
+		.expect("session finished without results")
+}
+
+/// Loads the single capture of a session
+pub fn load_only_capture(results: &ScreenshotResults) -> RgbaImage {
+	assert_eq!(
+		results.captured_paths.len(),
+		1,
+		"{:?}",
+		results.captured_paths
+	);
+	image::open(&results.captured_paths[0]).unwrap().to_rgba8()
+}
+
+/// Asserts every pixel of `image` is within one step of `expected` per channel
+pub fn assert_solid(image: &RgbaImage, expected: [u8; 4]) {
+	for (x, y, pixel) in image.enumerate_pixels() {
+		let close = pixel
+			.0
+			.iter()
+			.zip(expected)
+			.all(|(&a, b)| a.abs_diff(b) <= 1);
+		assert!(
+			close,
+			"pixel ({x}, {y}) is {:?}, expected {expected:?}",
+			pixel.0
+		);
+	}
+}
@@ -0,0 +1,32 @@
+//! Captures of a multisampled camera read the resolved render target.
+
+mod common;
+
+use bevy::prelude::*;
+
+use common::{assert_solid, empty_scene_config, load_only_capture, run_capture};
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn msaa_capture_has_output_size() {
+	let dir = tempfile::tempdir().unwrap();
+	let config = empty_scene_config(dir.path()).with_msaa(4);
+
+	let image = load_only_capture(&run_capture(config));
+
+	assert_eq!(image.dimensions(), (64, 32));
+	assert!(!image.as_raw().is_empty());
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn msaa_capture_of_solid_clear_is_the_clear_color() {
+	let dir = tempfile::tempdir().unwrap();
+	let config = empty_scene_config(dir.path())
+		.with_msaa(4)
+		.with_clear_color(Color::srgb_u8(200, 40, 90));
+
+	let image = load_only_capture(&run_capture(config));
+
+	assert_solid(&image, [200, 40, 90, 255]);
+}
@@ -0,0 +1,50 @@
+//! Single headless screenshot of the cube scene via `capture_once`.
+//!
+//! Writes `output/capture_once.png`.
+
+use bevy::prelude::*;
+use bevy_screenshot_harness::capture_once;
+
+fn main() {
+	let camera = Transform::from_xyz(5.0, 3.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+
+	if let Err(e) = capture_once(
+		|app| {
+			app.add_systems(Startup, setup_scene);
+		},
+		camera,
+		1280,
+		720,
+		"output/capture_once.png",
+	) {
+		eprintln!("capture failed: {e}");
+		std::process::exit(1);
+	}
+}
+
+fn setup_scene(
+	mut commands: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	commands.spawn((
+		Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
+		MeshMaterial3d(materials.add(StandardMaterial {
+			base_color: Color::srgb(0.8, 0.3, 0.2),
+			metallic: 0.3,
+			perceptual_roughness: 0.5,
+			..default()
+		})),
+		Transform::from_xyz(0.0, 0.5, 0.0),
+	));
+
+	commands.spawn((
+		Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, 10.0))),
+		MeshMaterial3d(materials.add(StandardMaterial {
+			base_color: Color::srgb(0.4, 0.4, 0.45),
+			metallic: 0.0,
+			perceptual_roughness: 0.9,
+			..default()
+		})),
+	));
+}